@@ -87,17 +87,11 @@ impl<R: Read> Iterator for CharsIter<R> {
 }
 
 pub fn main() {
-    unsafe {
-        init::init_target_infos();
-        init::init_targets();
-        init::init_target_mcs();
-        init::init_asm_printers();
-    }
+    let targets_initialized = init::TargetsInitialized::native().unwrap();
 
-    let target_triple = target::default_triple();
+    let target_machine = target::TargetMachine::native(targets_initialized, target::OptLevel::None).unwrap();
+    let target_triple = target_machine.triple();
     println!("{:?}", &target_triple);
-    let target = target::Target::from_triple(&target_triple).unwrap();
-    let target_machine = target::TargetMachine::new(target, &target_triple, const_cstr!("generic").as_cstr(), const_cstr!("").as_cstr(), target::LLVMCodeGenOptLevel::LLVMCodeGenLevelNone, target::LLVMRelocMode::LLVMRelocDefault, target::LLVMCodeModel::LLVMCodeModelDefault);
     let data_layout = target_machine.data_layout();
     println!("{:?}", data_layout.as_string());
 
@@ -116,6 +110,7 @@ pub fn main() {
         module.set_target_triple(&target_triple);
 
         let mut fpm = FunctionPassManager::new(&module);
+        target_machine.add_analysis_passes(&mut fpm);
         fpm.add_basic_alias_analysis();
         fpm.add_instruction_combine();
         fpm.add_reassociate();
@@ -151,7 +146,7 @@ pub fn main() {
         }
 
         print!("Writing to file...");
-        target_machine.emit_module_to_file(&module, const_cstr!("output.o").as_cstr(), target::LLVMCodeGenFileType::LLVMObjectFile).unwrap();
+        target_machine.emit_module_to_file(&module, const_cstr!("output.o").as_cstr(), target::CodeGenFileType::Object).unwrap();
         println!(" done.");
 
     });
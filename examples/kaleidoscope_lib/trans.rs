@@ -25,7 +25,7 @@ impl<'cid, 'context, 'mid, 'module> Context<'cid, 'context, 'mid, 'module> {
 
     pub fn trans_expr<'fid: 'block, 'block>(&mut self, expr: &ast::Expr, fbuilder: &mut llvm::FunctionBuilder<'cid, 'mid, 'fid, 'block>, builder: &mut llvm::PositionedBuilder<'cid, 'context, 'mid, 'fid, 'block>, named_values: &HashMap<&str, &'block Value<'cid, 'mid, 'fid, Type<'cid>>>) -> Result<&'block Value<'cid, 'mid, 'fid, Type<'cid>>, &'static str> where 'module: 'block {
         match *expr {
-            ast::Expr::Number(value) => Ok(Constant::f64(value, self.context).as_value()),
+            ast::Expr::Number(value) => Ok(upcast(Constant::f64(value, self.context).as_value())),
             ast::Expr::Variable(ref name) => named_values.get(&**name).cloned().ok_or("Unknown name in trans"),
             ast::Expr::BinaryOp(op, ref lhs, ref rhs) => {
                 let lhs_val = self.trans_expr(lhs, fbuilder, builder, named_values)?;
@@ -38,11 +38,11 @@ impl<'cid, 'context, 'mid, 'module> Context<'cid, 'context, 'mid, 'module> {
                     '/' => Ok(builder.fdiv(lhs_val, rhs_val, const_cstr!("divtmp").as_cstr())),
                     '<' => {
                         let cmp = builder.fcmp(LLVMRealPredicate::LLVMRealULT, lhs_val, rhs_val, const_cstr!("cmptmp").as_cstr());
-                        Ok(builder.ui_to_fp(cmp, Type::f64(self.context), const_cstr!("convtmp").as_cstr()))
+                        Ok(builder.ui_to_fp(cmp, upcast(Type::f64(self.context)), const_cstr!("convtmp").as_cstr()))
                     },
                     '>' => {
                         let cmp = builder.fcmp(LLVMRealPredicate::LLVMRealUGT, lhs_val, rhs_val, const_cstr!("cmptmp").as_cstr());
-                        Ok(builder.ui_to_fp(cmp, Type::f64(self.context), const_cstr!("convtmp").as_cstr()))
+                        Ok(builder.ui_to_fp(cmp, upcast(Type::f64(self.context)), const_cstr!("convtmp").as_cstr()))
                     },
                     _ => Err("Unknown operation in trans")
                 }
@@ -57,7 +57,7 @@ impl<'cid, 'context, 'mid, 'module> Context<'cid, 'context, 'mid, 'module> {
 
                         let arg_vals = args.iter().map(|arg| self.trans_expr(arg, fbuilder, builder, named_values).unwrap()).collect::<Vec<_>>();
 
-                        Ok(builder.call(func.function_type(), upcast(func), &arg_vals, const_cstr!("calltmp").as_cstr()))
+                        Ok(builder.call(func.function_type(), func.as_value(), &arg_vals, const_cstr!("calltmp").as_cstr()).as_value())
                     },
                     None => Err("Calling function that does not exist")
                 }
@@ -68,7 +68,7 @@ impl<'cid, 'context, 'mid, 'module> Context<'cid, 'context, 'mid, 'module> {
                 let (cont_label, cont_block) = fbuilder.append_basic_block(const_cstr!("ifcont").as_cstr(), self.context);
 
                 let cond_val = self.trans_expr(cond_expr, fbuilder, builder, named_values)?;
-                let cond_val = builder.fcmp(LLVMRealPredicate::LLVMRealONE, cond_val, Constant::f64(0.0, self.context).as_value(), const_cstr!("ifcond").as_cstr());
+                let cond_val = builder.fcmp(LLVMRealPredicate::LLVMRealONE, cond_val, upcast(Constant::f64(0.0, self.context).as_value()), const_cstr!("ifcond").as_cstr());
                 builder.cond_br(cond_val, then_label, else_label);
 
                 builder.position_at_end(then_block);
@@ -82,7 +82,7 @@ impl<'cid, 'context, 'mid, 'module> Context<'cid, 'context, 'mid, 'module> {
                 let else_label = builder.get_position();
 
                 builder.position_at_end(cont_block);
-                let phi = builder.phi(Type::f64(self.context), const_cstr!("iftmp").as_cstr());
+                let phi = builder.phi(upcast::<_,Type>(Type::f64(self.context)), const_cstr!("iftmp").as_cstr());
                 phi.add_incoming_branch(then_val, then_label);
                 phi.add_incoming_branch(else_val, else_label);
                 Ok(phi.as_value())
@@ -96,7 +96,7 @@ impl<'cid, 'context, 'mid, 'module> Context<'cid, 'context, 'mid, 'module> {
             return Err("Redefinition of already defined function");
          }
 
-        let f64_type = Type::f64(self.context);
+        let f64_type = upcast::<_,Type>(Type::f64(self.context));
         let arg_types = repeat(f64_type).take(proto.args.len()).collect::<Vec<_>>();
         let func_type = Type::function(&arg_types, f64_type, false);
 
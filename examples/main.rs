@@ -95,5 +95,52 @@ fn main() {
 
             function.dump()
         }
+
+        {
+            let square_ty = llvm::Type::typed_function::<(llvm::I32,), llvm::I32>(&context);
+            let square = module_builder.add_function(const_cstr!("typed_square").as_cstr(), square_ty);
+            id::with(|function_id| {
+                let function_builder = square.builder(function_id);
+                let (arg,) = function_builder.typed_params();
+                let (_, entry) = function_builder.append_basic_block(const_cstr!("entry").as_cstr(), &context);
+                let builder = builder.position_at_end(entry);
+
+                let ret = builder.mul(arg, arg, const_cstr!("square").as_cstr());
+                builder.ret(upcast(ret));
+            });
+            square.dump();
+
+            let fourth_ty = llvm::Type::typed_function::<(llvm::I32,), llvm::I32>(&context);
+            let fourth = module_builder.add_function(const_cstr!("typed_fourth").as_cstr(), fourth_ty);
+            id::with(|function_id| {
+                let function_builder = fourth.builder(function_id);
+                let (arg,) = function_builder.typed_params();
+                let (_, entry) = function_builder.append_basic_block(const_cstr!("entry").as_cstr(), &context);
+                let builder = builder.position_at_end(entry);
+
+                let squared = builder.call(square_ty, square.label().as_value(), (arg,), const_cstr!("squared").as_cstr()).as_value();
+                let ret = builder.call(square_ty, square.label().as_value(), (squared,), const_cstr!("fourth").as_cstr()).as_value();
+                builder.ret(upcast(ret));
+            });
+            fourth.dump()
+        }
+
+        // The assembly below is x86-64 only.
+        if cfg!(target_arch = "x86_64") {
+            module_builder.set_inline_asm(const_cstr!(".globl tsc_shift\ntsc_shift = 32").as_cstr());
+
+            let read_tsc_ty = llvm::Type::typed_function::<(), llvm::I64>(&context);
+            let rdtsc = llvm::InlineAsm::new(read_tsc_ty, const_cstr!("rdtsc\n\tshlq $$32, %rdx\n\torq %rdx, %rax").as_cstr(), const_cstr!("={rax},~{rdx}").as_cstr(), true, false, llvm::AsmDialect::ATT);
+            let read_tsc = module_builder.add_function(const_cstr!("read_tsc").as_cstr(), read_tsc_ty);
+            id::with(|function_id| {
+                let function_builder = read_tsc.builder(function_id);
+                let (_, entry) = function_builder.append_basic_block(const_cstr!("entry").as_cstr(), &context);
+                let builder = builder.position_at_end(entry);
+
+                let tsc = builder.call(read_tsc_ty, rdtsc.as_value(), (), const_cstr!("tsc").as_cstr()).as_value();
+                builder.ret(upcast(tsc));
+            });
+            read_tsc.dump()
+        }
     });
 }
@@ -0,0 +1,108 @@
+//! `#[derive(LlvmType)]` for `llvm-safe`.
+//!
+//! Deriving `LlvmType` on a `#[repr(C)]` struct implements `TypeMarker` and `StaticTypeMarker`
+//! from `llvm_safe::llvm::signature` for it, with the LLVM type being a literal struct of the
+//! field types. It also implements `RustLayout`, and every field type must itself implement
+//! `RustLayout`, which is the case for the primitive integer and float types, `bool`, thin
+//! pointers, arrays and other derived structs. Pure markers like `I32` or `Ptr<T>` are rejected,
+//! since their Rust layout says nothing about the LLVM type they name.
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use] extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use syn::{Data, DeriveInput, Error, Fields, Meta, NestedMeta};
+
+/// Derives `TypeMarker`, `StaticTypeMarker` and `RustLayout` for a `#[repr(C)]` struct.
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate llvm_safe_derive;
+/// # extern crate llvm_safe;
+/// # use llvm_safe::llvm::{Ptr, I32};
+/// #[derive(LlvmType)]
+/// #[repr(C)]
+/// struct Markers {
+///     value: I32,
+///     pointer: Ptr<I32>
+/// }
+/// # fn main() { }
+/// ```
+#[proc_macro_derive(LlvmType)]
+pub fn derive_llvm_type(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into()
+    }
+}
+
+fn expand(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let packed = repr_c_packed(input)?;
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics, "LlvmType cannot be derived for generic structs"));
+    }
+
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => fields.named.iter().map(|field| &field.ty).collect::<Vec<_>>(),
+            Fields::Unnamed(ref fields) => fields.unnamed.iter().map(|field| &field.ty).collect(),
+            Fields::Unit => Vec::new()
+        },
+        _ => return Err(Error::new(Span::call_site(), "LlvmType can only be derived for structs"))
+    };
+
+    let name = &input.ident;
+    Ok(quote! {
+        unsafe impl<'cid> ::llvm_safe::llvm::signature::TypeMarker<'cid> for #name {
+            type Type = ::llvm_safe::llvm::StructType<'cid>;
+        }
+
+        unsafe impl<'cid> ::llvm_safe::llvm::signature::StaticTypeMarker<'cid> for #name {
+            fn get<'ctx>(context: &'ctx ::llvm_safe::llvm::Context<'cid>) -> &'ctx ::llvm_safe::llvm::StructType<'cid> {
+                fn field<'ctx, 'cid, T: ::llvm_safe::llvm::signature::RustLayout<'cid>>(
+                    context: &'ctx ::llvm_safe::llvm::Context<'cid>
+                ) -> &'ctx ::llvm_safe::llvm::Type<'cid> where T::Type: 'ctx {
+                    ::llvm_safe::inheritance::upcast(T::get(context))
+                }
+
+                ::llvm_safe::llvm::Type::struct_(&[#(field::<#fields>(context)),*], #packed, context)
+            }
+        }
+
+        unsafe impl<'cid> ::llvm_safe::llvm::signature::RustLayout<'cid> for #name { }
+    })
+}
+
+/// Checks that the struct is `#[repr(C)]`, returning whether it is also `#[repr(packed)]`.
+fn repr_c_packed(input: &DeriveInput) -> Result<bool, Error> {
+    let mut c = false;
+    let mut packed = false;
+
+    for attr in &input.attrs {
+        if !attr.path.is_ident("repr") {
+            continue;
+        }
+
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new_spanned(meta, "unrecognized repr"))
+        };
+        for item in list.nested {
+            match item {
+                NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("C") => c = true,
+                NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("packed") => packed = true,
+                item => return Err(Error::new_spanned(item, "LlvmType only supports repr(C) and repr(packed)"))
+            }
+        }
+    }
+
+    if c {
+        Ok(packed)
+    } else {
+        Err(Error::new(Span::call_site(), "LlvmType can only be derived for #[repr(C)] structs"))
+    }
+}
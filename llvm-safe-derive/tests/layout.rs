@@ -0,0 +1,89 @@
+extern crate llvm_safe;
+#[macro_use] extern crate llvm_safe_derive;
+#[macro_use] extern crate memoffset;
+
+use std::mem;
+
+use llvm_safe::id;
+use llvm_safe::llvm::{init, target, Context, DataLayout};
+use llvm_safe::llvm::signature::StaticTypeMarker;
+use llvm_safe::owned::Owned;
+
+#[derive(LlvmType)]
+#[repr(C)]
+#[allow(dead_code)]
+struct Inner {
+    flag: bool,
+    value: f64
+}
+
+#[derive(LlvmType)]
+#[repr(C)]
+#[allow(dead_code)]
+struct Node {
+    tag: u8,
+    count: u32,
+    next: *mut Node,
+    inner: Inner,
+    small: i16,
+    values: [f32; 3],
+    len: usize
+}
+
+#[derive(LlvmType)]
+#[repr(C, packed)]
+#[allow(dead_code)]
+struct Packed {
+    tag: u8,
+    value: u64
+}
+
+fn host_data_layout() -> Owned<DataLayout> {
+    let init = init::TargetsInitialized::native().unwrap();
+
+    let machine = target::TargetMachine::builder(init).build().unwrap();
+    machine.data_layout()
+}
+
+#[test]
+fn offsets_match_rust() {
+    let data_layout = host_data_layout();
+    id::with(|context_id| {
+        let context = Context::new(context_id);
+
+        let node = Node::get(&context);
+        let offsets = [
+            offset_of!(Node, tag),
+            offset_of!(Node, count),
+            offset_of!(Node, next),
+            offset_of!(Node, inner),
+            offset_of!(Node, small),
+            offset_of!(Node, values),
+            offset_of!(Node, len)
+        ];
+        assert_eq!(node.element_count() as usize, offsets.len());
+        for (index, &offset) in offsets.iter().enumerate() {
+            assert_eq!(data_layout.offset_of_element(node, index as u32), offset as u64, "field {}", index);
+        }
+        assert_eq!(data_layout.abi_size_of(node), mem::size_of::<Node>() as u64);
+        assert_eq!(data_layout.abi_alignment_of(node), mem::align_of::<Node>() as u32);
+
+        let inner = Inner::get(&context);
+        assert_eq!(data_layout.offset_of_element(inner, 1), offset_of!(Inner, value) as u64);
+        assert_eq!(data_layout.abi_size_of(inner), mem::size_of::<Inner>() as u64);
+    });
+}
+
+#[test]
+fn packed_offsets_match_rust() {
+    let data_layout = host_data_layout();
+    id::with(|context_id| {
+        let context = Context::new(context_id);
+
+        let packed = Packed::get(&context);
+        assert!(packed.is_packed());
+        assert_eq!(data_layout.offset_of_element(packed, 1), 1);
+        assert_eq!(data_layout.abi_size_of(packed), mem::size_of::<Packed>() as u64);
+        assert_eq!(data_layout.abi_alignment_of(packed), mem::align_of::<Packed>() as u32);
+    });
+}
@@ -0,0 +1,91 @@
+use llvm_sys::prelude::*;
+use llvm_sys::core::*;
+
+use std::marker::PhantomData;
+
+use id::IdRef;
+use inheritance::DerivesFrom;
+use opaque::Opaque;
+
+use llvm::{Constant, InModule, UsableIn, Value, PointerType};
+use llvm::global::{Linkage, Visibility, DLLStorageClass};
+
+pub struct GlobalAlias<'cid, 'mid, Ty: ?Sized> {
+    _context_id: IdRef<'cid>,
+    _module_id: IdRef<'mid>,
+    _type: PhantomData<Ty>,
+    _opaque: Opaque
+}
+unsafe impl<'cid, 'mid, SpecificTy: DerivesFrom<GeneralTy> + ?Sized, GeneralTy: ?Sized> DerivesFrom<GlobalAlias<'cid, 'mid, GeneralTy>> for GlobalAlias<'cid, 'mid, SpecificTy> { }
+unsafe impl<'cid, 'mid, 'fid, General: ?Sized, Ty: ?Sized> DerivesFrom<General> for GlobalAlias<'cid, 'mid, Ty> where Value<'cid, 'mid, 'fid, PointerType<'cid, Ty>>: DerivesFrom<General> { }
+
+impl<'cid, 'mid, Ty: ?Sized> GlobalAlias<'cid, 'mid, Ty> {
+    pub fn aliasee(&self) -> &Constant<'cid, PointerType<'cid, Ty>, InModule<'mid>> {
+        unsafe {
+            &*(LLVMAliasGetAliasee(self.as_raw()) as *mut Constant<PointerType<Ty>, InModule>)
+        }
+    }
+
+    pub fn set_aliasee<Module: UsableIn<'mid>>(&mut self, aliasee: &Constant<'cid, PointerType<'cid, Ty>, Module>) {
+        unsafe {
+            LLVMAliasSetAliasee(self.as_raw(), aliasee.as_raw());
+        }
+    }
+
+    pub fn linkage(&self) -> Linkage {
+        unsafe {
+            Linkage::from_raw(LLVMGetLinkage(self.as_raw()))
+        }
+    }
+
+    pub fn set_linkage(&mut self, linkage: Linkage) {
+        unsafe {
+            LLVMSetLinkage(self.as_raw(), linkage.as_raw());
+        }
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        unsafe {
+            Visibility::from_raw(LLVMGetVisibility(self.as_raw()))
+        }
+    }
+
+    pub fn set_visibility(&mut self, visibility: Visibility) {
+        unsafe {
+            LLVMSetVisibility(self.as_raw(), visibility.as_raw());
+        }
+    }
+
+    pub fn dll_storage_class(&self) -> DLLStorageClass {
+        unsafe {
+            DLLStorageClass::from_raw(LLVMGetDLLStorageClass(self.as_raw()))
+        }
+    }
+
+    pub fn set_dll_storage_class(&mut self, class: DLLStorageClass) {
+        unsafe {
+            LLVMSetDLLStorageClass(self.as_raw(), class.as_raw());
+        }
+    }
+
+    pub fn downcast_value<'a, 'fid>(value: &'a Value<'cid, 'mid, 'fid, PointerType<'cid, Ty>>) -> Result<&'a GlobalAlias<'cid, 'mid, Ty>, ()> {
+        unsafe {
+            let ret = LLVMIsAGlobalAlias(value.as_raw());
+            if ret.is_null() {
+                Err(())
+            } else {
+                Ok(&*(ret as *mut GlobalAlias<Ty>))
+            }
+        }
+    }
+
+    pub fn as_value<'fid>(&self) -> &Value<'cid, 'mid, 'fid, PointerType<'cid, Ty>> {
+        unsafe {
+            &*(self as *const _ as *const Value<PointerType<Ty>>)
+        }
+    }
+
+    pub fn as_raw(&self) -> LLVMValueRef {
+        self.as_value().as_raw()
+    }
+}
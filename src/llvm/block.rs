@@ -1,8 +1,14 @@
 use llvm_sys::prelude::*;
+use llvm_sys::core::*;
+
+use std::marker::PhantomData;
+use std::ptr;
 
 use id::IdRef;
 use opaque::Opaque;
 
+use llvm::{FunctionLabel, Instruction, InstructionBuilder};
+
 pub struct BasicBlock<'cid, 'mid, 'fid> {
     _context_id: IdRef<'cid>,
     _module_id: IdRef<'mid>,
@@ -11,11 +17,142 @@ pub struct BasicBlock<'cid, 'mid, 'fid> {
 }
 
 impl<'cid, 'mid, 'fid> BasicBlock<'cid, 'mid, 'fid> {
+    /// The function containing this block.
+    pub fn parent(&self) -> &FunctionLabel<'cid, 'mid> {
+        unsafe {
+            &*(LLVMGetBasicBlockParent(self.as_raw()) as *mut FunctionLabel)
+        }
+    }
+
+    /// Iterates over the instructions in this block, from first to last. Use `rev` to walk them
+    /// backwards.
+    pub fn instructions<'block>(&'block self) -> BlockInstructions<'cid, 'mid, 'fid, 'block> {
+        unsafe {
+            BlockInstructions {
+                _block: PhantomData,
+                front: LLVMGetFirstInstruction(self.as_raw()),
+                back: LLVMGetLastInstruction(self.as_raw())
+            }
+        }
+    }
+
+    /// Iterates over the instructions in this block with mutable handles, so that they can be
+    /// moved or detached along the way.
+    pub fn instructions_mut<'block>(&'block mut self) -> BlockInstructionsMut<'cid, 'mid, 'fid, 'block> {
+        BlockInstructionsMut {
+            _block: PhantomData,
+            inner: unsafe { LLVMGetFirstInstruction(self.as_raw()) }
+        }
+    }
+
+    pub fn first_instruction(&self) -> Option<&Instruction<'cid, 'mid, 'fid>> {
+        unsafe {
+            let instruction = LLVMGetFirstInstruction(self.as_raw());
+            if instruction.is_null() {
+                None
+            } else {
+                Some(&*(instruction as *mut Instruction))
+            }
+        }
+    }
+
+    pub fn last_instruction(&self) -> Option<&Instruction<'cid, 'mid, 'fid>> {
+        unsafe {
+            let instruction = LLVMGetLastInstruction(self.as_raw());
+            if instruction.is_null() {
+                None
+            } else {
+                Some(&*(instruction as *mut Instruction))
+            }
+        }
+    }
+
+    /// The instruction ending this block, or `None` if the block is still being built and does
+    /// not end in a terminator yet.
+    pub fn terminator(&self) -> Option<&Instruction<'cid, 'mid, 'fid>> {
+        unsafe {
+            let instruction = LLVMGetBasicBlockTerminator(self.as_raw());
+            if instruction.is_null() {
+                None
+            } else {
+                Some(&*(instruction as *mut Instruction))
+            }
+        }
+    }
+
     pub fn as_raw(&self) -> LLVMBasicBlockRef {
         self as *const BasicBlock as *mut BasicBlock as LLVMBasicBlockRef
     }
 }
 
+pub struct BlockInstructions<'cid: 'block, 'mid: 'block, 'fid: 'block, 'block> {
+    _block: PhantomData<&'block BasicBlock<'cid, 'mid, 'fid>>,
+    front: LLVMValueRef,
+    back: LLVMValueRef
+}
+
+impl<'cid: 'block, 'mid: 'block, 'fid: 'block, 'block> Iterator for BlockInstructions<'cid, 'mid, 'fid, 'block> {
+    type Item = &'block Instruction<'cid, 'mid, 'fid>;
+
+    fn next(&mut self) -> Option<&'block Instruction<'cid, 'mid, 'fid>> {
+        if self.front.is_null() {
+            None
+        } else {
+            unsafe {
+                let ret = Some(&*(self.front as *const Instruction));
+                if self.front == self.back {
+                    self.front = ptr::null_mut();
+                    self.back = ptr::null_mut();
+                } else {
+                    self.front = LLVMGetNextInstruction(self.front);
+                }
+                ret
+            }
+        }
+    }
+}
+
+impl<'cid: 'block, 'mid: 'block, 'fid: 'block, 'block> DoubleEndedIterator for BlockInstructions<'cid, 'mid, 'fid, 'block> {
+    fn next_back(&mut self) -> Option<&'block Instruction<'cid, 'mid, 'fid>> {
+        if self.back.is_null() {
+            None
+        } else {
+            unsafe {
+                let ret = Some(&*(self.back as *const Instruction));
+                if self.front == self.back {
+                    self.front = ptr::null_mut();
+                    self.back = ptr::null_mut();
+                } else {
+                    self.back = LLVMGetPreviousInstruction(self.back);
+                }
+                ret
+            }
+        }
+    }
+}
+
+pub struct BlockInstructionsMut<'cid: 'block, 'mid: 'block, 'fid: 'block, 'block> {
+    _block: PhantomData<&'block mut BasicBlock<'cid, 'mid, 'fid>>,
+    inner: LLVMValueRef
+}
+
+impl<'cid: 'block, 'mid: 'block, 'fid: 'block, 'block> Iterator for BlockInstructionsMut<'cid, 'mid, 'fid, 'block> {
+    type Item = &'block mut InstructionBuilder<'cid, 'mid, 'fid, 'block>;
+
+    fn next(&mut self) -> Option<&'block mut InstructionBuilder<'cid, 'mid, 'fid, 'block>> {
+        if self.inner.is_null() {
+            None
+        } else {
+            unsafe {
+                // Step past the instruction before handing it out, since it may be moved or detached
+                let ret = Some(&mut *(self.inner as *mut InstructionBuilder));
+                self.inner = LLVMGetNextInstruction(self.inner);
+                ret
+            }
+        }
+    }
+}
+
 pub struct Label<'fid> {
     _function_id: IdRef<'fid>
 }
@@ -1,5 +1,6 @@
 use std::ffi::CStr;
 use std::marker::PhantomData;
+use std::mem;
 
 use libc::c_uint;
 
@@ -11,7 +12,8 @@ use inheritance::{upcast, DerivesFrom};
 use opaque::Opaque;
 use owned::{Owned, DropInPlace};
 
-use llvm::{Context, BasicBlock, Label, Value, Phi, Alloca, Type, FunctionType, IntegerType, PointerType};
+use llvm::{Context, BasicBlock, Label, Instruction, DetachedInstruction, Value, Phi, Alloca, Call, Type, FunctionType, IntegerType, IntOperand, FloatOperand, PointerType};
+use llvm::signature::{ArgValues, ReturnMarker, Callee};
 
 pub struct Builder<'cid: 'context, 'context> {
     _context: PhantomData<&'context Context<'cid>>,
@@ -51,12 +53,23 @@ pub struct PositionedBuilder<'cid: 'context, 'context: 'block, 'mid: 'block, 'fi
     _opaque: Opaque
 }
 
-macro_rules! binop_impl {
+macro_rules! float_binop_impl {
     ( $($(#[$doc:meta])* $rust_name:ident, $c_name:ident)* )  => { $(
         $(#[$doc])*
-        pub fn $rust_name(&mut self, lhs: &Value<'cid, 'mid, 'fid, Type<'cid>>, rhs: &Value<'cid, 'mid, 'fid, Type<'cid>>, name: &CStr) -> &'block Value<'cid, 'mid, 'fid, Type<'cid>> {
+        pub fn $rust_name<Ty: FloatOperand<'cid> + ?Sized>(&mut self, lhs: &Value<'cid, 'mid, 'fid, Ty>, rhs: &Value<'cid, 'mid, 'fid, Ty>, name: &CStr) -> &'block Value<'cid, 'mid, 'fid, Ty> {
             unsafe {
-                &*($c_name(self.as_raw(), lhs.as_raw(), rhs.as_raw(), name.as_ptr()) as *const Value<Type>)
+                &*($c_name(self.as_raw(), lhs.as_raw(), rhs.as_raw(), name.as_ptr()) as *const Value<Ty>)
+            }
+        }
+    )* };
+}
+
+macro_rules! int_binop_impl {
+    ( $($(#[$doc:meta])* $rust_name:ident, $c_name:ident)* )  => { $(
+        $(#[$doc])*
+        pub fn $rust_name<Ty: IntOperand<'cid> + ?Sized>(&mut self, lhs: &Value<'cid, 'mid, 'fid, Ty>, rhs: &Value<'cid, 'mid, 'fid, Ty>, name: &CStr) -> &'block Value<'cid, 'mid, 'fid, Ty> {
+            unsafe {
+                &*($c_name(self.as_raw(), lhs.as_raw(), rhs.as_raw(), name.as_ptr()) as *const Value<Ty>)
             }
         }
     )* };
@@ -102,7 +115,7 @@ impl<'cid, 'context, 'mid, 'fid, 'block> PositionedBuilder<'cid, 'context, 'mid,
         }
     }
 
-binop_impl!{
+int_binop_impl!{
     /// Creates an [`add`][langref] instruction.
     ///
     /// Corresponds to `CreateAdd` ([C++][C++]) and `LLVMBuildAdd` ([C][C], [Rust][Rust])
@@ -133,16 +146,6 @@ binop_impl!{
     /// [Rust]: LLVMBuildNUWAdd
     add_nuw, LLVMBuildNUWAdd
 
-    /// Creates an [`fadd`][langref] instruction.
-    ///
-    /// Corresponds to `CreateFAdd` ([C++][C++]) and `LLVMBuildFAdd` ([C][C], [Rust][Rust])
-    ///
-    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fadd-instruction
-    /// [C++]: https://llvm.org/doxygen/classllvm_1_1IRBuilder.html#a0878a27006251ab2e48984206df19d84
-    /// [C]: https://llvm.org/doxygen/group__LLVMCCoreInstructionBuilder.html#ga18ffa5e08ab18e49ad9c4f45d69c36e7
-    /// [Rust]: LLVMBuildFAdd
-    fadd, LLVMBuildFAdd
-
     /// Creates a [`sub`][langref] instruction.
     ///
     /// Corresponds to `CreateSub` ([C++][C++]) and `LLVMBuildSub` ([C][C], [Rust][Rust])
@@ -173,16 +176,6 @@ binop_impl!{
     /// [Rust]: LLVMBuildNUWSub
     sub_nuw, LLVMBuildNUWSub
 
-    /// Creates an [`fsub`][langref] instruction.
-    ///
-    /// Corresponds to `CreateFSub` ([C++][C++]) and `LLVMBuildFSub` ([C][C], [Rust][Rust]).
-    ///
-    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fsub-instruction
-    /// [C++]: https://llvm.org/doxygen/classllvm_1_1IRBuilder.html#ac574f9f74f0a8f34461a1d530990d24b
-    /// [C]: https://llvm.org/doxygen/group__LLVMCCoreInstructionBuilder.html#ga6d37daa2fcb7c972cb69c50dce528d36
-    /// [Rust]: LLVMBuildFSub
-    fsub, LLVMBuildFSub
-
     /// Creates a [`mul`][langref] instruction.
     ///
     /// Corresponds to `CreateMul` ([C++][C++]) and `LLVMBuildMul` ([C][C], [Rust][Rust]).
@@ -213,16 +206,6 @@ binop_impl!{
     /// [Rust]: LLVMBuildNUWMul
     mul_nuw, LLVMBuildNUWMul
 
-    /// Creates an [`fmul`][langref] instruction.
-    ///
-    /// Corresponds to `CreateFMul` ([C++][C++]) and `LLVMBuildFMul` ([C][C], [Rust][Rust]).
-    ///
-    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fmul-instruction
-    /// [C++]: https://llvm.org/doxygen/classllvm_1_1IRBuilder.html#a179d92834e61875dbe397728553af68f
-    /// [C]: https://llvm.org/doxygen/group__LLVMCCoreInstructionBuilder.html#ga1a1136d38e745260a9384d6b79ff1149
-    /// [Rust]: LLVMBuildFMul
-    fmul, LLVMBuildFMul
-
     /// Creates a [`udiv`][langref] instruction.
     ///
     /// Corresponds to `CreateUDiv` ([C++][C++]) and `LLVMBuildUDiv` ([C][C], [Rust][Rust]).
@@ -263,17 +246,6 @@ binop_impl!{
     /// [Rust]: LLVMBuildExactSDiv
     sdiv_exact, LLVMBuildExactSDiv
 
-    /// Creates an [`fdiv`][langref] instruction.
-    ///
-    /// Corresponds to `CreateFDiv` ([C++][C++]) and `LLVMBuildFDiv` ([C][C], [Rust][Rust]).
-    ///
-    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fdiv-instruction
-    /// [C++]: https://llvm.org/doxygen/classllvm_1_1IRBuilder.html#abf156f5610c0dd8fae21230aac333c01
-    /// [C]: https://llvm.org/doxygen/group__LLVMCCoreInstructionBuilder.html#ga110c2b670e7190b0d642c5019049632f
-    /// [Rust]: LLVMBuildFDiv
-    fdiv, LLVMBuildFDiv
-
-
     /// Creates a [`urem`][langref] instruction.
     ///
     /// Corresponds to `CreateURem` ([C++][C++]) and `LLVMBuildURem` ([C][C], [Rust][Rust]).
@@ -294,16 +266,6 @@ binop_impl!{
     /// [Rust]: LLVMBuildSRem
     srem, LLVMBuildSRem
 
-    /// Creates an [`frem`][langref] instruction.
-    ///
-    /// Corresponds to `CreateFRem` ([C++][C++]) and `LLVMBuildFRem` ([C][C], [Rust][Rust]).
-    ///
-    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#frem-instruction
-    /// [C++]: https://llvm.org/doxygen/classllvm_1_1IRBuilder.html#a22983adbba8de3386ffec124e3d754b5
-    /// [C]: https://llvm.org/doxygen/group__LLVMCCoreInstructionBuilder.html#ga3461da41ca02505baa785beadace31a7
-    /// [Rust]: LLVMBuildFRem
-    frem, LLVMBuildFRem
-
     /// Creates a [`shl`][langref] instruction.
     ///
     /// Corresponds to `CreateShl` ([C++][C++]) and `LLVMBuildShl` ([C][C], [Rust][Rust]).
@@ -365,6 +327,58 @@ binop_impl!{
     xor, LLVMBuildXor
 }
 
+float_binop_impl!{
+    /// Creates an [`fadd`][langref] instruction.
+    ///
+    /// Corresponds to `CreateFAdd` ([C++][C++]) and `LLVMBuildFAdd` ([C][C], [Rust][Rust])
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fadd-instruction
+    /// [C++]: https://llvm.org/doxygen/classllvm_1_1IRBuilder.html#a0878a27006251ab2e48984206df19d84
+    /// [C]: https://llvm.org/doxygen/group__LLVMCCoreInstructionBuilder.html#ga18ffa5e08ab18e49ad9c4f45d69c36e7
+    /// [Rust]: LLVMBuildFAdd
+    fadd, LLVMBuildFAdd
+
+    /// Creates an [`fsub`][langref] instruction.
+    ///
+    /// Corresponds to `CreateFSub` ([C++][C++]) and `LLVMBuildFSub` ([C][C], [Rust][Rust]).
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fsub-instruction
+    /// [C++]: https://llvm.org/doxygen/classllvm_1_1IRBuilder.html#ac574f9f74f0a8f34461a1d530990d24b
+    /// [C]: https://llvm.org/doxygen/group__LLVMCCoreInstructionBuilder.html#ga6d37daa2fcb7c972cb69c50dce528d36
+    /// [Rust]: LLVMBuildFSub
+    fsub, LLVMBuildFSub
+
+    /// Creates an [`fmul`][langref] instruction.
+    ///
+    /// Corresponds to `CreateFMul` ([C++][C++]) and `LLVMBuildFMul` ([C][C], [Rust][Rust]).
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fmul-instruction
+    /// [C++]: https://llvm.org/doxygen/classllvm_1_1IRBuilder.html#a179d92834e61875dbe397728553af68f
+    /// [C]: https://llvm.org/doxygen/group__LLVMCCoreInstructionBuilder.html#ga1a1136d38e745260a9384d6b79ff1149
+    /// [Rust]: LLVMBuildFMul
+    fmul, LLVMBuildFMul
+
+    /// Creates an [`fdiv`][langref] instruction.
+    ///
+    /// Corresponds to `CreateFDiv` ([C++][C++]) and `LLVMBuildFDiv` ([C][C], [Rust][Rust]).
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fdiv-instruction
+    /// [C++]: https://llvm.org/doxygen/classllvm_1_1IRBuilder.html#abf156f5610c0dd8fae21230aac333c01
+    /// [C]: https://llvm.org/doxygen/group__LLVMCCoreInstructionBuilder.html#ga110c2b670e7190b0d642c5019049632f
+    /// [Rust]: LLVMBuildFDiv
+    fdiv, LLVMBuildFDiv
+
+    /// Creates an [`frem`][langref] instruction.
+    ///
+    /// Corresponds to `CreateFRem` ([C++][C++]) and `LLVMBuildFRem` ([C][C], [Rust][Rust]).
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#frem-instruction
+    /// [C++]: https://llvm.org/doxygen/classllvm_1_1IRBuilder.html#a22983adbba8de3386ffec124e3d754b5
+    /// [C]: https://llvm.org/doxygen/group__LLVMCCoreInstructionBuilder.html#ga3461da41ca02505baa785beadace31a7
+    /// [Rust]: LLVMBuildFRem
+    frem, LLVMBuildFRem
+}
+
     /// Creates a [`sub 0, <value>`][langref] instruction.
     ///
     /// Corresponds to `CreateNeg` ([C++][C++]) and `LLVMBuildNeg` ([C][C], [Rust][Rust]).
@@ -373,9 +387,9 @@ binop_impl!{
     /// [C++]: https://llvm.org/doxygen/classllvm_1_1IRBuilder.html#afd7e0f4bb499af728f9325e41afc344c
     /// [C]: https://llvm.org/doxygen/group__LLVMCCoreInstructionBuilder.html#gaf748025627b03f4f2659b006b127b758
     /// [Rust]: LLVMBuildNeg
-    pub fn neg(&mut self, value: &Value<'cid, 'mid, 'fid, Type<'cid>>, name: &CStr) -> &'block Value<'cid, 'mid, 'fid, Type<'cid>> {
+    pub fn neg<Ty: IntOperand<'cid> + ?Sized>(&mut self, value: &Value<'cid, 'mid, 'fid, Ty>, name: &CStr) -> &'block Value<'cid, 'mid, 'fid, Ty> {
         unsafe {
-            &*(LLVMBuildNeg(self.as_raw(), value.as_raw(), name.as_ptr()) as *const Value<Type>)
+            &*(LLVMBuildNeg(self.as_raw(), value.as_raw(), name.as_ptr()) as *const Value<Ty>)
         }
     }
 
@@ -387,9 +401,9 @@ binop_impl!{
     /// [C++]: https://llvm.org/doxygen/classllvm_1_1IRBuilder.html#afadf0ed4391eedf48ea806b83e7d6263
     /// [C]: https://llvm.org/doxygen/group__LLVMCCoreInstructionBuilder.html#ga93de3da5c9ab84b1fb2a167b96e37e1a
     /// [Rust]: LLVMBuildFNeg
-    pub fn fneg(&mut self, value: &Value<'cid, 'mid, 'fid, Type<'cid>>, name: &CStr) -> &'block Value<'cid, 'mid, 'fid, Type<'cid>> {
+    pub fn fneg<Ty: FloatOperand<'cid> + ?Sized>(&mut self, value: &Value<'cid, 'mid, 'fid, Ty>, name: &CStr) -> &'block Value<'cid, 'mid, 'fid, Ty> {
         unsafe {
-            &*(LLVMBuildFNeg(self.as_raw(), value.as_raw(), name.as_ptr()) as *const Value<Type>)
+            &*(LLVMBuildFNeg(self.as_raw(), value.as_raw(), name.as_ptr()) as *const Value<Ty>)
         }
     }
 
@@ -401,9 +415,9 @@ binop_impl!{
     /// [C++]: https://llvm.org/doxygen/classllvm_1_1IRBuilder.html#a5141946c874cc73e682aa0b3b4cdb561
     /// [C]: https://llvm.org/doxygen/group__LLVMCCoreInstructionBuilder.html#ga05d09bddf47c45595a9f87b38e5ea924
     /// [Rust]: LLVMBuildNot
-    pub fn not(&mut self, value: &Value<'cid, 'mid, 'fid, Type<'cid>>, name: &CStr) -> &'block Value<'cid, 'mid, 'fid, Type<'cid>> {
+    pub fn not<Ty: IntOperand<'cid> + ?Sized>(&mut self, value: &Value<'cid, 'mid, 'fid, Ty>, name: &CStr) -> &'block Value<'cid, 'mid, 'fid, Ty> {
         unsafe {
-            &*(LLVMBuildNot(self.as_raw(), value.as_raw(), name.as_ptr()) as *const Value<Type>)
+            &*(LLVMBuildNot(self.as_raw(), value.as_raw(), name.as_ptr()) as *const Value<Ty>)
         }
     }
 
@@ -415,9 +429,9 @@ binop_impl!{
     /// [C++]: https://llvm.org/doxygen/classllvm_1_1IRBuilder.html#a103d309fa238e186311cbeb961b5bcf4
     /// [C]: https://llvm.org/doxygen/group__LLVMCCoreInstructionBuilder.html#ga73559fb71fcb2caee54375378f49d174
     /// [Rust]: LLVMBuildICmp
-    pub fn icmp(&mut self, pred: LLVMIntPredicate, lhs: &Value<'cid, 'mid, 'fid, Type<'cid>>, rhs: &Value<'cid, 'mid, 'fid, Type<'cid>>, name: &CStr) -> &'block Value<'cid, 'mid, 'fid, Type<'cid>> {
+    pub fn icmp<Ty: IntOperand<'cid> + ?Sized>(&mut self, pred: LLVMIntPredicate, lhs: &Value<'cid, 'mid, 'fid, Ty>, rhs: &Value<'cid, 'mid, 'fid, Ty>, name: &CStr) -> &'block Value<'cid, 'mid, 'fid, Ty::Bool> {
         unsafe {
-            &*(LLVMBuildICmp(self.as_raw(), pred, lhs.as_raw(), rhs.as_raw(), name.as_ptr()) as *const Value<Type>)
+            &*(LLVMBuildICmp(self.as_raw(), pred, lhs.as_raw(), rhs.as_raw(), name.as_ptr()) as *const Value<Ty::Bool>)
         }
     }
 
@@ -429,9 +443,9 @@ binop_impl!{
     /// [C++]: https://llvm.org/doxygen/classllvm_1_1IRBuilder.html#a02e6bb4130ab2bd333e859dd2565d962
     /// [C]: https://llvm.org/doxygen/group__LLVMCCoreInstructionBuilder.html#ga220dc4644a992417951e7f42fa0bc408
     /// [Rust]: LLVMBuildFCmp
-    pub fn fcmp(&mut self, pred: LLVMRealPredicate, lhs: &Value<'cid, 'mid, 'fid, Type<'cid>>, rhs: &Value<'cid, 'mid, 'fid, Type<'cid>>, name: &CStr) -> &'block Value<'cid, 'mid, 'fid, Type<'cid>> {
+    pub fn fcmp<Ty: FloatOperand<'cid> + ?Sized>(&mut self, pred: LLVMRealPredicate, lhs: &Value<'cid, 'mid, 'fid, Ty>, rhs: &Value<'cid, 'mid, 'fid, Ty>, name: &CStr) -> &'block Value<'cid, 'mid, 'fid, Ty::Bool> {
         unsafe {
-            &*(LLVMBuildFCmp(self.as_raw(), pred, lhs.as_raw(), rhs.as_raw(), name.as_ptr()) as *const Value<Type>)
+            &*(LLVMBuildFCmp(self.as_raw(), pred, lhs.as_raw(), rhs.as_raw(), name.as_ptr()) as *const Value<Ty::Bool>)
         }
     }
 
@@ -683,6 +697,20 @@ cast_impl!{
         }
     }
 
+    /// Creates a private, constant global holding a null-terminated copy of `string` and returns an
+    /// `i8*` pointing to its first character.
+    ///
+    /// Corresponds to `CreateGlobalStringPtr` ([C++][C++]) and `LLVMBuildGlobalStringPtr` ([C][C], [Rust][Rust]).
+    ///
+    /// [C++]: https://llvm.org/doxygen/classllvm_1_1IRBuilder.html
+    /// [C]: https://llvm.org/doxygen/group__LLVMCCoreInstructionBuilder.html
+    /// [Rust]: LLVMBuildGlobalStringPtr
+    pub fn global_string_ptr(&mut self, string: &CStr, name: &CStr) -> &'block Value<'cid, 'mid, 'fid, PointerType<'cid, IntegerType<'cid>>> {
+        unsafe {
+            &*(LLVMBuildGlobalStringPtr(self.as_raw(), string.as_ptr(), name.as_ptr()) as *const Value<PointerType<IntegerType>>)
+        }
+    }
+
     /// Creates a [`phi`][langref] instruction.
     ///
     /// Corresponds to `CreatePHI` ([C++][C++]) and `LLVMBuildPhi` ([C][C], [Rust][Rust]).
@@ -705,9 +733,15 @@ cast_impl!{
     /// [C++]: https://llvm.org/doxygen/classllvm_1_1IRBuilder.html#a2f2b90f6238b8dd8ffd39ec6b05f5772
     /// [C]: https://llvm.org/doxygen/group__LLVMCCoreInstructionBuilder.html#ga821864790c90dc5193078c4e17b8cb09
     /// [Rust]: LLVMBuildCall2
-    pub fn call(&mut self, ty: &FunctionType<'cid>, func: &Value<'cid, 'mid, 'fid, Type<'cid>>, args: &[&Value<'cid, 'mid, 'fid, Type<'cid>>], name: &CStr) -> &'block Value<'cid, 'mid, 'fid, Type<'cid>> {
+    ///
+    /// With an untyped `FunctionType<'cid>`, `args` is a slice of untyped values. With a
+    /// `FunctionType<'cid, Args, Ret>` from `Type::typed_function`, `args` is a tuple of values
+    /// matching `Args` and the result is typed according to `Ret`.
+    pub fn call<'a, Args, Ret, F>(&mut self, ty: &FunctionType<'cid, Args, Ret>, func: &Value<'cid, 'mid, 'fid, F>, args: Args::Values, name: &CStr) -> &'block mut Call<'cid, 'mid, 'fid, Ret::Type>
+            where Args: ArgValues<'a, 'cid, 'mid, 'fid> + ?Sized, Ret: ReturnMarker<'cid> + ?Sized, F: Callee<'cid, Args, Ret> + ?Sized {
+        let mut args = Args::raw_values(&args);
         unsafe {
-            &*(LLVMBuildCall2(self.as_raw(), upcast::<_,Type>(ty).as_raw(), func.as_raw(), args.as_ptr() as *const LLVMValueRef as *mut LLVMValueRef, args.len() as u32, name.as_ptr()) as *const Value<Type>)
+            &mut *(LLVMBuildCall2(self.as_raw(), upcast::<_,Type>(ty).as_raw(), func.as_raw(), args.as_mut_ptr(), args.len() as u32, name.as_ptr()) as *mut Call<Ret::Type>)
         }
     }
 
@@ -754,6 +788,17 @@ cast_impl!{
     }
 
 
+    /// Inserts an instruction that was unlinked with `InstructionBuilder::remove_from_parent`.
+    pub fn insert<'detached>(&mut self, instruction: DetachedInstruction<'cid, 'mid, 'fid, 'detached>, name: &CStr) -> &'block Instruction<'cid, 'mid, 'fid> {
+        unsafe {
+            let raw = instruction.as_raw();
+            // The instruction now has a place, so it must not be put back on drop
+            mem::forget(instruction);
+            LLVMInsertIntoBuilderWithName(self.as_raw(), raw, name.as_ptr());
+            &*(raw as *mut Instruction)
+        }
+    }
+
     pub fn get_position(&self) -> &'block Label<'fid> {
         unsafe {
             &*(LLVMGetInsertBlock(self.as_raw()) as *mut Label as *const Label)
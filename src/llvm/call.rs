@@ -0,0 +1,156 @@
+use llvm_sys::prelude::*;
+use llvm_sys::core::*;
+use llvm_sys::LLVMCallConv;
+
+use libc::{c_int, c_uint};
+
+use inheritance::DerivesFrom;
+
+use llvm::Value;
+
+macro_rules! call_conv_impl {
+    ( $($(#[$doc:meta])* $rust_name:ident, $c_name:ident)* )  => {
+        /// A function's [calling convention][langref].
+        ///
+        /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#calling-conventions
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum CallConv {
+            $(
+                $(#[$doc])*
+                $rust_name,
+            )*
+            /// A target-specific calling convention without a name, written `cc <n>`.
+            Other(c_uint)
+        }
+
+        impl CallConv {
+            pub fn from_raw(raw: c_uint) -> CallConv {
+                match raw {
+                    $(raw if raw == LLVMCallConv::$c_name as c_uint => CallConv::$rust_name,)*
+                    raw => CallConv::Other(raw)
+                }
+            }
+
+            pub fn as_raw(self) -> c_uint {
+                match self {
+                    $(CallConv::$rust_name => LLVMCallConv::$c_name as c_uint,)*
+                    CallConv::Other(raw) => raw
+                }
+            }
+        }
+    };
+}
+
+call_conv_impl!{
+    /// The C calling convention, used by default.
+    C, LLVMCCallConv
+    /// `fastcc`, which permits guaranteed tail calls.
+    Fast, LLVMFastCallConv
+    /// `coldcc`, for rarely called code.
+    Cold, LLVMColdCallConv
+    /// `ghccc`, used by the Glasgow Haskell Compiler.
+    GHC, LLVMGHCCallConv
+    HiPE, LLVMHiPECallConv
+    WebKitJS, LLVMWebKitJSCallConv
+    AnyReg, LLVMAnyRegCallConv
+    PreserveMost, LLVMPreserveMostCallConv
+    PreserveAll, LLVMPreserveAllCallConv
+    Swift, LLVMSwiftCallConv
+    CXXFastTLS, LLVMCXXFASTTLSCallConv
+    X86Stdcall, LLVMX86StdcallCallConv
+    X86Fastcall, LLVMX86FastcallCallConv
+    ARMAPCS, LLVMARMAPCSCallConv
+    ARMAAPCS, LLVMARMAAPCSCallConv
+    ARMAAPCSVFP, LLVMARMAAPCSVFPCallConv
+    MSP430Intr, LLVMMSP430INTRCallConv
+    X86ThisCall, LLVMX86ThisCallCallConv
+    PTXKernel, LLVMPTXKernelCallConv
+    PTXDevice, LLVMPTXDeviceCallConv
+    SPIRFunc, LLVMSPIRFUNCCallConv
+    SPIRKernel, LLVMSPIRKERNELCallConv
+    IntelOCLBI, LLVMIntelOCLBICallConv
+    X86_64SysV, LLVMX8664SysVCallConv
+    Win64, LLVMWin64CallConv
+    /// `x86_vectorcallcc`, the Microsoft `__vectorcall` convention.
+    X86VectorCall, LLVMX86VectorCallCallConv
+    HHVM, LLVMHHVMCallConv
+    HHVMC, LLVMHHVMCCallConv
+    X86Intr, LLVMX86INTRCallConv
+    AVRIntr, LLVMAVRINTRCallConv
+    AVRSignal, LLVMAVRSIGNALCallConv
+    AVRBuiltin, LLVMAVRBUILTINCallConv
+    AMDGPUVS, LLVMAMDGPUVSCallConv
+    AMDGPUGS, LLVMAMDGPUGSCallConv
+    AMDGPUPS, LLVMAMDGPUPSCallConv
+    AMDGPUCS, LLVMAMDGPUCSCallConv
+    AMDGPUKernel, LLVMAMDGPUKERNELCallConv
+    X86RegCall, LLVMX86RegCallCallConv
+    AMDGPUHS, LLVMAMDGPUHSCallConv
+    MSP430Builtin, LLVMMSP430BUILTINCallConv
+    AMDGPULS, LLVMAMDGPULSCallConv
+    AMDGPUES, LLVMAMDGPUESCallConv
+}
+
+pub struct Call<'cid, 'mid, 'fid, Ty: ?Sized> {
+    _super: Value<'cid, 'mid, 'fid, Ty>
+}
+unsafe impl<'cid, 'mid, 'fid, SpecificTy: DerivesFrom<GeneralTy> + ?Sized, GeneralTy: ?Sized> DerivesFrom<Call<'cid, 'mid, 'fid, GeneralTy>> for Call<'cid, 'mid, 'fid, SpecificTy> { }
+unsafe impl<'cid, 'mid, 'fid, General: ?Sized, Ty: ?Sized> DerivesFrom<General> for Call<'cid, 'mid, 'fid, Ty> where Value<'cid, 'mid, 'fid, Ty>: DerivesFrom<General> { }
+
+impl<'cid, 'mid, 'fid, Ty: ?Sized> Call<'cid, 'mid, 'fid, Ty> {
+    pub fn call_conv(&self) -> CallConv {
+        unsafe {
+            CallConv::from_raw(LLVMGetInstructionCallConv(self.as_raw()))
+        }
+    }
+
+    /// Sets the calling convention used for this call. This should match the calling convention of
+    /// the callee, or the behavior is undefined.
+    pub fn set_call_conv(&mut self, call_conv: CallConv) {
+        unsafe {
+            LLVMSetInstructionCallConv(self.as_raw(), call_conv.as_raw());
+        }
+    }
+
+    /// Whether this call has any tail call marker. `musttail` calls, which can come from parsed IR,
+    /// are also reported as tail calls.
+    pub fn is_tail_call(&self) -> bool {
+        unsafe {
+            LLVMIsTailCall(self.as_raw()) != 0
+        }
+    }
+
+    /// Marks this call with the [`tail`][langref] marker, or removes any tail call marker.
+    ///
+    /// `tail` is only a hint that the callee does not access the caller's stack. The `musttail` and
+    /// `notail` markers, which guarantee or forbid tail call optimization, are not supported:
+    /// the LLVM 8 C API has no function to set them.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#call-instruction
+    pub fn set_tail_call(&mut self, is_tail_call: bool) {
+        unsafe {
+            LLVMSetTailCall(self.as_raw(), is_tail_call as c_int);
+        }
+    }
+
+    pub fn downcast_value<'a>(value: &'a Value<'cid, 'mid, 'fid, Ty>) -> Result<&'a Call<'cid, 'mid, 'fid, Ty>, ()> {
+        unsafe {
+            let ret = LLVMIsACallInst(value.as_raw());
+            if ret.is_null() {
+                Err(())
+            } else {
+                Ok(&*(ret as *mut Call<Ty>))
+            }
+        }
+    }
+
+    pub fn as_value(&self) -> &Value<'cid, 'mid, 'fid, Ty> {
+        unsafe {
+            &*(self as *const _ as *const Value<Ty>)
+        }
+    }
+
+    pub fn as_raw(&self) -> LLVMValueRef {
+        self.as_value().as_raw()
+    }
+}
@@ -0,0 +1,67 @@
+use llvm_sys::prelude::LLVMComdatRef;
+use llvm_sys::comdat::{LLVMComdatSelectionKind, LLVMGetComdatSelectionKind, LLVMSetComdatSelectionKind};
+
+use id::IdRef;
+use opaque::Opaque;
+
+/// How the linker resolves [COMDAT][langref] groups with the same name from different object files.
+///
+/// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#comdats
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ComdatSelectionKind {
+    /// The linker may choose any COMDAT.
+    Any,
+    /// The data referenced by the COMDAT must be the same.
+    ExactMatch,
+    /// The linker will choose the largest COMDAT.
+    Largest,
+    /// No other module may specify this COMDAT.
+    NoDuplicates,
+    /// The data referenced by the COMDAT must be the same size.
+    SameSize
+}
+
+impl ComdatSelectionKind {
+    pub fn from_raw(raw: LLVMComdatSelectionKind) -> ComdatSelectionKind {
+        match raw {
+            LLVMComdatSelectionKind::LLVMAnyComdatSelectionKind => ComdatSelectionKind::Any,
+            LLVMComdatSelectionKind::LLVMExactMatchComdatSelectionKind => ComdatSelectionKind::ExactMatch,
+            LLVMComdatSelectionKind::LLVMLargestComdatSelectionKind => ComdatSelectionKind::Largest,
+            LLVMComdatSelectionKind::LLVMNoDuplicatesComdatSelectionKind => ComdatSelectionKind::NoDuplicates,
+            LLVMComdatSelectionKind::LLVMSameSizeComdatSelectionKind => ComdatSelectionKind::SameSize
+        }
+    }
+
+    pub fn as_raw(self) -> LLVMComdatSelectionKind {
+        match self {
+            ComdatSelectionKind::Any => LLVMComdatSelectionKind::LLVMAnyComdatSelectionKind,
+            ComdatSelectionKind::ExactMatch => LLVMComdatSelectionKind::LLVMExactMatchComdatSelectionKind,
+            ComdatSelectionKind::Largest => LLVMComdatSelectionKind::LLVMLargestComdatSelectionKind,
+            ComdatSelectionKind::NoDuplicates => LLVMComdatSelectionKind::LLVMNoDuplicatesComdatSelectionKind,
+            ComdatSelectionKind::SameSize => LLVMComdatSelectionKind::LLVMSameSizeComdatSelectionKind
+        }
+    }
+}
+
+pub struct Comdat<'mid> {
+    _module_id: IdRef<'mid>,
+    _opaque: Opaque
+}
+
+impl<'mid> Comdat<'mid> {
+    pub fn selection_kind(&self) -> ComdatSelectionKind {
+        unsafe {
+            ComdatSelectionKind::from_raw(LLVMGetComdatSelectionKind(self.as_raw()))
+        }
+    }
+
+    pub fn set_selection_kind(&mut self, kind: ComdatSelectionKind) {
+        unsafe {
+            LLVMSetComdatSelectionKind(self.as_raw(), kind.as_raw());
+        }
+    }
+
+    pub fn as_raw(&self) -> LLVMComdatRef {
+        self as *const Comdat as *mut Comdat as LLVMComdatRef
+    }
+}
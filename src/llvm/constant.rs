@@ -1,5 +1,6 @@
 use llvm_sys::prelude::*;
 use llvm_sys::core::*;
+use llvm_sys::{LLVMTypeKind, LLVMIntPredicate, LLVMRealPredicate};
 
 use std::marker::PhantomData;
 use libc::{c_char, c_uint, c_int};
@@ -8,15 +9,51 @@ use id::IdRef;
 use inheritance::{upcast, DerivesFrom};
 use opaque::Opaque;
 
-use llvm::{Context, Type, IntegerType, PointerType, ArrayType, Value};
+use llvm::{Context, Type, IntegerType, Int, IntWidth, IntOperand, FloatType, Float, FloatKind, FloatOperand, PointerType, ArrayType, VectorType, StructType, Value};
 
-pub struct Constant<'cid, Ty: ?Sized> {
+/// A constant value. Constants are shared by every module in a context, except that the address
+/// of a global or function is only meaningful in its own module. `Module` tracks this: constants
+/// built from scratch are `AnyModule` and can be used anywhere, while anything derived from a
+/// global's address is `InModule<'mid>`.
+pub struct Constant<'cid, Ty: ?Sized, Module = AnyModule> {
     _context: IdRef<'cid>,
     _type: PhantomData<Ty>,
+    _module: PhantomData<Module>,
     _opaque: Opaque
 }
-unsafe impl<'cid, SpecificTy: DerivesFrom<GeneralTy> + ?Sized, GeneralTy: ?Sized> DerivesFrom<Constant<'cid, GeneralTy>> for Constant<'cid, SpecificTy> { }
-unsafe impl<'cid, 'mid, 'fid, General: ?Sized, Ty: ?Sized> DerivesFrom<General> for Constant<'cid, Ty> where Value<'cid, 'mid, 'fid, Ty>: DerivesFrom<General> { }
+unsafe impl<'cid, SpecificTy: DerivesFrom<GeneralTy> + ?Sized, GeneralTy: ?Sized, Module> DerivesFrom<Constant<'cid, GeneralTy, Module>> for Constant<'cid, SpecificTy, Module> { }
+unsafe impl<'cid, 'mid, SpecificTy: DerivesFrom<GeneralTy> + ?Sized, GeneralTy: ?Sized> DerivesFrom<Constant<'cid, GeneralTy, InModule<'mid>>> for Constant<'cid, SpecificTy, AnyModule> { }
+unsafe impl<'cid, 'mid, 'fid, General: ?Sized, Ty: ?Sized, Module: UsableIn<'mid>> DerivesFrom<General> for Constant<'cid, Ty, Module> where Value<'cid, 'mid, 'fid, Ty>: DerivesFrom<General> { }
+
+/// The brand of constants that do not refer to any global, so can be used in every module.
+pub enum AnyModule { }
+
+/// The brand of constants that may refer to globals of the module `'mid`.
+pub struct InModule<'mid> {
+    _module_id: IdRef<'mid>
+}
+
+/// Brands of constants that can be used in the module `'mid`.
+pub trait UsableIn<'mid> { }
+impl<'mid> UsableIn<'mid> for AnyModule { }
+impl<'mid> UsableIn<'mid> for InModule<'mid> { }
+
+/// The brand of a constant expression built from constants branded `Self` and `Other`.
+pub trait Join<Other> {
+    type Output;
+}
+impl Join<AnyModule> for AnyModule {
+    type Output = AnyModule;
+}
+impl<'mid> Join<InModule<'mid>> for AnyModule {
+    type Output = InModule<'mid>;
+}
+impl<'mid> Join<AnyModule> for InModule<'mid> {
+    type Output = InModule<'mid>;
+}
+impl<'mid> Join<InModule<'mid>> for InModule<'mid> {
+    type Output = InModule<'mid>;
+}
 
 impl<'cid> Constant<'cid, IntegerType<'cid>> {
     pub fn bool<'ctx>(value: bool, context: &'ctx Context<'cid>) -> &'ctx Constant<'cid, IntegerType<'cid>> {
@@ -27,21 +64,119 @@ impl<'cid> Constant<'cid, IntegerType<'cid>> {
         Constant::integer(value as u64, Type::i8(context), false)
     }
 
+    pub fn i16<'ctx>(value: i16, context: &'ctx Context<'cid>) -> &'ctx Constant<'cid, IntegerType<'cid>> {
+        Constant::integer(value as u64, Type::i16(context), false)
+    }
+
     pub fn i32<'ctx>(value: i32, context: &'ctx Context<'cid>) -> &'ctx Constant<'cid, IntegerType<'cid>> {
         Constant::integer(value as u64, Type::i32(context), false)
     }
 
-    pub fn integer<'ctx>(value: u64, ty: &'ctx IntegerType<'cid>, signed: bool) -> &'ctx Constant<'cid, IntegerType<'cid>> {
+    pub fn i64<'ctx>(value: i64, context: &'ctx Context<'cid>) -> &'ctx Constant<'cid, IntegerType<'cid>> {
+        Constant::integer(value as u64, Type::i64(context), false)
+    }
+
+    pub fn i128<'ctx>(value: i128, context: &'ctx Context<'cid>) -> &'ctx Constant<'cid, IntegerType<'cid>> {
+        Constant::integer_words(&[value as u64, (value >> 64) as u64], Type::i128(context))
+    }
+
+    /// Creates an integer constant whose width is known statically, so that it can be used with
+    /// the typed arithmetic operations. `value` is sign extended to widths over 64 bits and
+    /// truncated to narrower ones.
+    pub fn int<'ctx, Width: IntWidth + ?Sized>(value: i64, context: &'ctx Context<'cid>) -> &'ctx Constant<'cid, Int<'cid, Width>> {
+        Constant::integer(value as u64, Type::int::<Width>(context), true)
+    }
+}
+
+impl<'cid, Ty: DerivesFrom<IntegerType<'cid>> + ?Sized> Constant<'cid, Ty> {
+    pub fn integer<'ctx>(value: u64, ty: &'ctx Ty, signed: bool) -> &'ctx Constant<'cid, Ty> {
         unsafe {
-            &*(LLVMConstInt(upcast::<_,Type>(ty).as_raw(), value, signed as c_int) as *mut Constant<IntegerType>)
+            &*(LLVMConstInt(upcast::<_,Type>(upcast::<_,IntegerType>(ty)).as_raw(), value, signed as c_int) as *mut Constant<Ty>)
         }
     }
+
+    /// Creates an integer constant of any width from 64-bit words, least significant word first.
+    /// Missing high words are zero and excess ones are truncated.
+    pub fn integer_words<'ctx>(words: &[u64], ty: &'ctx Ty) -> &'ctx Constant<'cid, Ty> {
+        unsafe {
+            &*(LLVMConstIntOfArbitraryPrecision(upcast::<_,Type>(upcast::<_,IntegerType>(ty)).as_raw(), words.len() as c_uint, words.as_ptr()) as *mut Constant<Ty>)
+        }
+    }
+
+    /// Parses an integer constant written in base `radix`, which must be 2, 8, 10, 16 or 36. The
+    /// text may start with a `-` sign. Returns `None` if the radix is unsupported, `text` is not
+    /// a valid number in that radix, or `text` has more digits than `ty` can hold.
+    ///
+    /// LLVM only bounds the number of digits, so a value with few enough digits that is still too
+    /// large for `ty`, like `"999"` for `i8`, is truncated.
+    pub fn integer_from_str<'ctx>(text: &str, radix: u8, ty: &'ctx Ty) -> Option<&'ctx Constant<'cid, Ty>> {
+        match radix {
+            2 | 8 | 10 | 16 | 36 => { },
+            _ => return None
+        }
+        let digits = match text.chars().next() {
+            Some('-') => &text[1..],
+            _ => text
+        };
+        if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix as u32)) {
+            return None;
+        }
+
+        unsafe {
+            let raw_ty = upcast::<_,Type>(upcast::<_,IntegerType>(ty)).as_raw();
+            // These are the limits that `APInt::fromString` asserts, and radix 36 has none.
+            let bits = LLVMGetIntTypeWidth(raw_ty) as usize;
+            let fits = match radix {
+                2 => digits.len() <= bits,
+                8 => (digits.len() - 1) * 3 <= bits,
+                10 => (digits.len() - 1) * 64 / 22 <= bits,
+                16 => (digits.len() - 1) * 4 <= bits,
+                _ => true
+            };
+            if !fits {
+                return None;
+            }
+
+            Some(&*(LLVMConstIntOfStringAndSize(raw_ty, text.as_ptr() as *const c_char, text.len() as c_uint, radix) as *mut Constant<Ty>))
+        }
+    }
+}
+
+impl<'cid> Constant<'cid, FloatType<'cid>> {
+    pub fn f32<'ctx>(value: f32, context: &'ctx Context<'cid>) -> &'ctx Constant<'cid, FloatType<'cid>> {
+        Constant::real(value as f64, Type::f32(context))
+    }
+
+    pub fn f64<'ctx>(value: f64, context: &'ctx Context<'cid>) -> &'ctx Constant<'cid, FloatType<'cid>> {
+        Constant::real(value, Type::f64(context))
+    }
+
+    /// Creates a floating point constant whose format is known statically, so that it can be used
+    /// with the typed arithmetic operations. `value` is rounded if it isn't representable.
+    pub fn float<'ctx, Kind: FloatKind + ?Sized>(value: f64, context: &'ctx Context<'cid>) -> &'ctx Constant<'cid, Float<'cid, Kind>> {
+        Constant::real(value, Type::float::<Kind>(context))
+    }
 }
 
-impl<'cid> Constant<'cid, Type<'cid>> {
-    pub fn f64<'ctx>(value: f64, context: &'ctx Context<'cid>) -> &'ctx Constant<'cid, Type<'cid>> {
+impl<'cid, Ty: DerivesFrom<FloatType<'cid>> + ?Sized> Constant<'cid, Ty> {
+    /// Creates a floating point constant of type `ty`, rounding `value` if it isn't representable.
+    pub fn real<'ctx>(value: f64, ty: &'ctx Ty) -> &'ctx Constant<'cid, Ty> {
+        unsafe {
+            &*(LLVMConstReal(upcast::<_,Type>(upcast::<_,FloatType>(ty)).as_raw(), value) as *mut Constant<Ty>)
+        }
+    }
+
+    /// Creates a floating point constant of type `ty` by parsing `text` directly in the format
+    /// of `ty`, without going through an `f64`. This gives exact values for wider types such as
+    /// `fp128`. Hexadecimal forms like `0x1p-3` are accepted as well as decimal ones like `-1.5e3`.
+    /// Returns `None` if `text` is not a number in one of those forms.
+    pub fn real_from_str<'ctx>(text: &str, ty: &'ctx Ty) -> Option<&'ctx Constant<'cid, Ty>> {
+        if !is_real_literal(text) {
+            return None;
+        }
+
         unsafe {
-            &*(LLVMConstReal(Type::f64(context).as_raw(), value) as *mut Constant<Type>)
+            Some(&*(LLVMConstRealOfStringAndSize(upcast::<_,Type>(upcast::<_,FloatType>(ty)).as_raw(), text.as_ptr() as *const c_char, text.len() as c_uint) as *mut Constant<Ty>))
         }
     }
 }
@@ -52,6 +187,482 @@ impl<'cid, Ty: ?Sized> Constant<'cid, Ty> {
             &*(LLVMConstNull(upcast(ty).as_raw()) as *mut Constant<Ty>)
         }
     }
+
+    pub fn undef<'ctx>(ty: &'ctx Ty) -> &'ctx Constant<'cid, Ty> where Ty: DerivesFrom<Type<'cid>> {
+        unsafe {
+            &*(LLVMGetUndef(upcast(ty).as_raw()) as *mut Constant<Ty>)
+        }
+    }
+
+    /// Creates a constant with every bit set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ty` is not an integer or floating point type or a vector of them.
+    pub fn all_ones<'ctx>(ty: &'ctx Ty) -> &'ctx Constant<'cid, Ty> where Ty: DerivesFrom<Type<'cid>> {
+        unsafe {
+            let raw_ty = upcast(ty).as_raw();
+            let scalar_ty = match LLVMGetTypeKind(raw_ty) {
+                LLVMTypeKind::LLVMVectorTypeKind => LLVMGetElementType(raw_ty),
+                _ => raw_ty
+            };
+            match LLVMGetTypeKind(scalar_ty) {
+                LLVMTypeKind::LLVMIntegerTypeKind |
+                LLVMTypeKind::LLVMHalfTypeKind |
+                LLVMTypeKind::LLVMFloatTypeKind |
+                LLVMTypeKind::LLVMDoubleTypeKind |
+                LLVMTypeKind::LLVMX86_FP80TypeKind |
+                LLVMTypeKind::LLVMFP128TypeKind |
+                LLVMTypeKind::LLVMPPC_FP128TypeKind => { },
+                _ => panic!("all_ones requires an integer or floating point type")
+            }
+            &*(LLVMConstAllOnes(raw_ty) as *mut Constant<Ty>)
+        }
+    }
+}
+
+impl<'cid, ElementTy: DerivesFrom<Type<'cid>> + ?Sized, Module> Constant<'cid, ArrayType<'cid, ElementTy>, Module> {
+    /// Creates a constant array of `element_ty` values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any element does not have exactly the type `element_ty`.
+    pub fn array<'ctx>(element_ty: &'ctx ElementTy, elements: &[&'ctx Constant<'cid, ElementTy, Module>]) -> &'ctx Constant<'cid, ArrayType<'cid, ElementTy>, Module> {
+        let raw_ty = upcast(element_ty).as_raw();
+        let mut raw_elements = raw_constants(elements);
+        assert!(raw_elements.iter().all(|&element| unsafe { LLVMTypeOf(element) } == raw_ty), "array element has the wrong type");
+        unsafe {
+            &*(LLVMConstArray(raw_ty, raw_elements.as_mut_ptr(), raw_elements.len() as c_uint) as *mut Constant<ArrayType<ElementTy>, Module>)
+        }
+    }
+}
+
+impl<'cid, ElementTy: DerivesFrom<Type<'cid>> + ?Sized, Module> Constant<'cid, VectorType<'cid, ElementTy>, Module> {
+    /// Creates a constant vector from its elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `elements` is empty or its elements do not all have the same type.
+    pub fn vector<'ctx>(elements: &[&'ctx Constant<'cid, ElementTy, Module>]) -> &'ctx Constant<'cid, VectorType<'cid, ElementTy>, Module> {
+        let mut raw_elements = raw_constants(elements);
+        assert!(!raw_elements.is_empty(), "vector constants must have at least one element");
+        let raw_ty = unsafe { LLVMTypeOf(raw_elements[0]) };
+        assert!(raw_elements.iter().all(|&element| unsafe { LLVMTypeOf(element) } == raw_ty), "vector elements must all have the same type");
+        unsafe {
+            &*(LLVMConstVector(raw_elements.as_mut_ptr(), raw_elements.len() as c_uint) as *mut Constant<VectorType<ElementTy>, Module>)
+        }
+    }
+}
+
+impl<'cid, Module> Constant<'cid, StructType<'cid>, Module> {
+    /// Creates a constant of a literal struct type whose fields are the types of `elements`.
+    pub fn struct_<'ctx>(elements: &[&'ctx Constant<'cid, Type<'cid>, Module>], packed: bool, context: &'ctx Context<'cid>) -> &'ctx Constant<'cid, StructType<'cid>, Module> {
+        let mut raw_elements = raw_constants(elements);
+        unsafe {
+            &*(LLVMConstStructInContext(context.as_raw(), raw_elements.as_mut_ptr(), raw_elements.len() as c_uint, packed as c_int) as *mut Constant<StructType, Module>)
+        }
+    }
+
+    /// Creates a constant of the named struct type `ty`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number or types of `elements` do not match the fields of `ty`.
+    pub fn named_struct<'ctx>(ty: &'ctx StructType<'cid>, elements: &[&'ctx Constant<'cid, Type<'cid>, Module>]) -> &'ctx Constant<'cid, StructType<'cid>, Module> {
+        let mut raw_elements = raw_constants(elements);
+        assert!(raw_elements.len() == ty.element_count() as usize, "wrong number of struct fields");
+        for (index, &element) in raw_elements.iter().enumerate() {
+            assert!(unsafe { LLVMTypeOf(element) } == ty.element_ty(index as c_uint).unwrap().as_raw(), "struct field has the wrong type");
+        }
+        unsafe {
+            &*(LLVMConstNamedStruct(upcast::<_,Type>(ty).as_raw(), raw_elements.as_mut_ptr(), raw_elements.len() as c_uint) as *mut Constant<StructType, Module>)
+        }
+    }
+}
+
+macro_rules! binop_impl {
+    ( $($(#[$doc:meta])* $rust_name:ident, $c_name:ident)* )  => { $(
+        $(#[$doc])*
+        pub fn $rust_name<'a, Other>(&'a self, rhs: &'a Constant<'cid, Ty, Other>) -> &'a Constant<'cid, Ty, Module::Output> where Module: Join<Other> {
+            unsafe {
+                &*($c_name(self.as_raw(), rhs.as_raw()) as *mut Constant<Ty, Module::Output>)
+            }
+        }
+    )* };
+}
+
+macro_rules! cast_impl {
+    ( $($(#[$doc:meta])* $rust_name:ident, $c_name:ident)* )  => { $(
+        $(#[$doc])*
+        pub fn $rust_name<'a, DestTy: DerivesFrom<Type<'cid>> + ?Sized>(&'a self, dest_ty: &'a DestTy) -> &'a Constant<'cid, DestTy, Module> {
+            unsafe {
+                &*($c_name(self.as_raw(), upcast(dest_ty).as_raw()) as *mut Constant<DestTy, Module>)
+            }
+        }
+    )* };
+}
+
+impl<'cid, Ty: IntOperand<'cid> + ?Sized, Module> Constant<'cid, Ty, Module> {
+binop_impl!{
+    /// Creates a constant [`add`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstAdd`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#add-instruction
+    add, LLVMConstAdd
+
+    /// Creates a constant [`add nsw`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstNSWAdd`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#add-instruction
+    add_nsw, LLVMConstNSWAdd
+
+    /// Creates a constant [`add nuw`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstNUWAdd`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#add-instruction
+    add_nuw, LLVMConstNUWAdd
+
+    /// Creates a constant [`sub`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstSub`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#sub-instruction
+    sub, LLVMConstSub
+
+    /// Creates a constant [`sub nsw`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstNSWSub`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#sub-instruction
+    sub_nsw, LLVMConstNSWSub
+
+    /// Creates a constant [`sub nuw`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstNUWSub`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#sub-instruction
+    sub_nuw, LLVMConstNUWSub
+
+    /// Creates a constant [`mul`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstMul`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#mul-instruction
+    mul, LLVMConstMul
+
+    /// Creates a constant [`mul nsw`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstNSWMul`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#mul-instruction
+    mul_nsw, LLVMConstNSWMul
+
+    /// Creates a constant [`mul nuw`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstNUWMul`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#mul-instruction
+    mul_nuw, LLVMConstNUWMul
+
+    /// Creates a constant [`udiv`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstUDiv`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#udiv-instruction
+    udiv, LLVMConstUDiv
+
+    /// Creates a constant [`udiv exact`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstExactUDiv`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#udiv-instruction
+    udiv_exact, LLVMConstExactUDiv
+
+    /// Creates a constant [`sdiv`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstSDiv`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#sdiv-instruction
+    sdiv, LLVMConstSDiv
+
+    /// Creates a constant [`sdiv exact`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstExactSDiv`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#sdiv-instruction
+    sdiv_exact, LLVMConstExactSDiv
+
+    /// Creates a constant [`urem`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstURem`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#urem-instruction
+    urem, LLVMConstURem
+
+    /// Creates a constant [`srem`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstSRem`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#srem-instruction
+    srem, LLVMConstSRem
+
+    /// Creates a constant [`shl`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstShl`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#shl-instruction
+    shl, LLVMConstShl
+
+    /// Creates a constant [`lshr`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstLShr`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#lshr-instruction
+    lshr, LLVMConstLShr
+
+    /// Creates a constant [`ashr`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstAShr`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#ashr-instruction
+    ashr, LLVMConstAShr
+
+    /// Creates a constant [`and`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstAnd`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#and-instruction
+    and, LLVMConstAnd
+
+    /// Creates a constant [`or`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstOr`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#or-instruction
+    or, LLVMConstOr
+
+    /// Creates a constant [`xor`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstXor`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#xor-instruction
+    xor, LLVMConstXor
+}
+
+    /// Creates a constant [`sub 0, <value>`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstNeg`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#sub-instruction
+    pub fn neg(&self) -> &Constant<'cid, Ty, Module> {
+        unsafe {
+            &*(LLVMConstNeg(self.as_raw()) as *mut Constant<Ty, Module>)
+        }
+    }
+
+    /// Creates a constant [`xor <value>, -1`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstNot`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#xor-instruction
+    pub fn not(&self) -> &Constant<'cid, Ty, Module> {
+        unsafe {
+            &*(LLVMConstNot(self.as_raw()) as *mut Constant<Ty, Module>)
+        }
+    }
+
+    /// Creates a constant [`icmp`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstICmp`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#icmp-instruction
+    pub fn icmp<'a, Other>(&'a self, pred: LLVMIntPredicate, rhs: &'a Constant<'cid, Ty, Other>) -> &'a Constant<'cid, Ty::Bool, Module::Output> where Module: Join<Other> {
+        unsafe {
+            &*(LLVMConstICmp(pred, self.as_raw(), rhs.as_raw()) as *mut Constant<Ty::Bool, Module::Output>)
+        }
+    }
+}
+
+impl<'cid, Ty: FloatOperand<'cid> + ?Sized, Module> Constant<'cid, Ty, Module> {
+binop_impl!{
+    /// Creates a constant [`fadd`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstFAdd`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fadd-instruction
+    fadd, LLVMConstFAdd
+
+    /// Creates a constant [`fsub`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstFSub`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fsub-instruction
+    fsub, LLVMConstFSub
+
+    /// Creates a constant [`fmul`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstFMul`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fmul-instruction
+    fmul, LLVMConstFMul
+
+    /// Creates a constant [`fdiv`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstFDiv`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fdiv-instruction
+    fdiv, LLVMConstFDiv
+
+    /// Creates a constant [`frem`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstFRem`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#frem-instruction
+    frem, LLVMConstFRem
+}
+
+    /// Creates a constant [`fneg`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstFNeg`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fneg-instruction
+    pub fn fneg(&self) -> &Constant<'cid, Ty, Module> {
+        unsafe {
+            &*(LLVMConstFNeg(self.as_raw()) as *mut Constant<Ty, Module>)
+        }
+    }
+
+    /// Creates a constant [`fcmp`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstFCmp`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fcmp-instruction
+    pub fn fcmp<'a, Other>(&'a self, pred: LLVMRealPredicate, rhs: &'a Constant<'cid, Ty, Other>) -> &'a Constant<'cid, Ty::Bool, Module::Output> where Module: Join<Other> {
+        unsafe {
+            &*(LLVMConstFCmp(pred, self.as_raw(), rhs.as_raw()) as *mut Constant<Ty::Bool, Module::Output>)
+        }
+    }
+}
+
+impl<'cid, Ty: ?Sized, Module> Constant<'cid, Ty, Module> {
+cast_impl!{
+    /// Creates a constant [`trunc`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstTrunc`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#trunc-to-instruction
+    trunc, LLVMConstTrunc
+
+    /// Creates a constant [`fptrunc`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstFPTrunc`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fptrunc-to-instruction
+    fp_trunc, LLVMConstFPTrunc
+
+    /// Creates a constant [`zext`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstZExt`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#zext-to-instruction
+    zext, LLVMConstZExt
+
+    /// Creates a constant [`sext`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstSExt`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#sext-to-instruction
+    sext, LLVMConstSExt
+
+    /// Creates a constant [`fpext`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstFPExt`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fpext-to-instruction
+    fp_ext, LLVMConstFPExt
+
+    /// Creates a constant [`fptoui`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstFPToUI`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fptoui-to-instruction
+    fp_to_ui, LLVMConstFPToUI
+
+    /// Creates a constant [`fptosi`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstFPToSI`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#fptosi-to-instruction
+    fp_to_si, LLVMConstFPToSI
+
+    /// Creates a constant [`uitofp`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstUIToFP`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#uitofp-to-instruction
+    ui_to_fp, LLVMConstUIToFP
+
+    /// Creates a constant [`sitofp`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstSIToFP`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#sitofp-to-instruction
+    si_to_fp, LLVMConstSIToFP
+
+    /// Creates a constant [`ptrtoint`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstPtrToInt`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#ptrtoint-to-instruction
+    ptr_to_int, LLVMConstPtrToInt
+
+    /// Creates a constant [`inttoptr`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstIntToPtr`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#inttoptr-to-instruction
+    int_to_ptr, LLVMConstIntToPtr
+
+    /// Creates a constant [`bitcast`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstBitCast`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#bitcast-to-instruction
+    bitcast, LLVMConstBitCast
+}
+}
+
+impl<'cid, Pointee: ?Sized, Module> Constant<'cid, PointerType<'cid, Pointee>, Module> {
+    /// Creates a constant [`getelementptr`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstGEP`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#getelementptr-instruction
+    pub fn gep<'a>(&'a self, indices: &[&'a Constant<'cid, IntegerType<'cid>>]) -> &'a Constant<'cid, PointerType<'cid, Type<'cid>>, Module> {
+        let mut raw_indices = raw_constants(indices);
+        unsafe {
+            &*(LLVMConstGEP(self.as_raw(), raw_indices.as_mut_ptr(), raw_indices.len() as c_uint) as *mut Constant<PointerType<Type>, Module>)
+        }
+    }
+
+    /// Creates a constant [`getelementptr inbounds`][langref] expression.
+    ///
+    /// Corresponds to `LLVMConstInBoundsGEP`.
+    ///
+    /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#getelementptr-instruction
+    pub fn in_bounds_gep<'a>(&'a self, indices: &[&'a Constant<'cid, IntegerType<'cid>>]) -> &'a Constant<'cid, PointerType<'cid, Type<'cid>>, Module> {
+        let mut raw_indices = raw_constants(indices);
+        unsafe {
+            &*(LLVMConstInBoundsGEP(self.as_raw(), raw_indices.as_mut_ptr(), raw_indices.len() as c_uint) as *mut Constant<PointerType<Type>, Module>)
+        }
+    }
+}
+
+fn raw_constants<'cid, Ty: ?Sized, Module>(constants: &[&Constant<'cid, Ty, Module>]) -> Vec<LLVMValueRef> {
+    constants.iter().map(|constant| constant.as_raw()).collect()
 }
 
 impl<'cid, Ty: ?Sized> Constant<'cid, PointerType<'cid, Ty>> {
@@ -70,24 +681,116 @@ impl<'cid> Constant<'cid, ArrayType<'cid, IntegerType<'cid>>> {
     }
 }
 
-impl<'cid, Ty: ?Sized> Constant<'cid, Ty> {
-    pub fn downcast_value<'a, 'mid, 'fid>(value: &'a Value<'cid, 'mid, 'fid, Ty>) -> Result<&'a Constant<'cid, Ty>, ()> {
+impl<'cid, Ty: ?Sized, Module> Constant<'cid, Ty, Module> {
+    /// Returns the value of an integer constant zero extended to 64 bits, or `None` if this is
+    /// not a simple integer constant or is wider than 64 bits.
+    pub fn zext_value(&self) -> Option<u64> {
         unsafe {
-            if LLVMIsConstant(value.as_raw()) != 0 {
-                Ok(&*(value.as_raw() as *mut Constant<Ty>))
+            match self.int_width() {
+                Some(width) if width <= 64 => Some(LLVMConstIntGetZExtValue(self.as_raw())),
+                _ => None
+            }
+        }
+    }
+
+    /// Returns the value of an integer constant sign extended to 64 bits, or `None` if this is
+    /// not a simple integer constant or is wider than 64 bits.
+    pub fn sext_value(&self) -> Option<i64> {
+        unsafe {
+            match self.int_width() {
+                Some(width) if width <= 64 => Some(LLVMConstIntGetSExtValue(self.as_raw())),
+                _ => None
+            }
+        }
+    }
+
+    /// Returns the value of a floating point constant converted to an `f64`, rounding if
+    /// necessary, or `None` if this is not a simple floating point constant.
+    pub fn double_value(&self) -> Option<f64> {
+        unsafe {
+            if LLVMIsAConstantFP(self.as_raw()).is_null() {
+                None
             } else {
-                Err(())
+                let mut loses_info = 0;
+                Some(LLVMConstRealGetDouble(self.as_raw(), &mut loses_info))
             }
         }
     }
 
-    pub fn as_value<'mid, 'fid>(&self) -> &Value<'cid, 'mid, 'fid, Ty> {
+    fn int_width(&self) -> Option<c_uint> {
+        unsafe {
+            if LLVMIsAConstantInt(self.as_raw()).is_null() {
+                None
+            } else {
+                Some(LLVMGetIntTypeWidth(LLVMTypeOf(self.as_raw())))
+            }
+        }
+    }
+
+    pub fn as_value<'mid, 'fid>(&self) -> &Value<'cid, 'mid, 'fid, Ty> where Module: UsableIn<'mid> {
         unsafe {
             &*(self as *const _ as *const Value<Ty>)
         }
     }
 
     pub fn as_raw(&self) -> LLVMValueRef {
-        self.as_value().as_raw()
+        self as *const Constant<'cid, Ty, Module> as *mut Constant<'cid, Ty, Module> as LLVMValueRef
+    }
+}
+
+impl<'cid, 'mid, Ty: ?Sized> Constant<'cid, Ty, InModule<'mid>> {
+    /// Views a constant value as a `Constant`. The value may be the address of a global, so the
+    /// constant stays branded with the value's module.
+    pub fn downcast_value<'a, 'fid>(value: &'a Value<'cid, 'mid, 'fid, Ty>) -> Result<&'a Constant<'cid, Ty, InModule<'mid>>, ()> {
+        unsafe {
+            if LLVMIsConstant(value.as_raw()) != 0 {
+                Ok(&*(value.as_raw() as *mut Constant<Ty, InModule>))
+            } else {
+                Err(())
+            }
+        }
+    }
+}
+
+// LLVM asserts that floating point literals are well formed instead of reporting errors, so check
+// for an optional sign followed by either decimal digits with an optional point and `e` exponent,
+// or `0x` and hexadecimal digits with an optional point and a required `p` exponent.
+fn is_real_literal(text: &str) -> bool {
+    let unsigned = strip_sign(text);
+    let is_hex = unsigned.starts_with("0x") || unsigned.starts_with("0X");
+    let (mantissa, exponent, radix) = if is_hex {
+        let hex = &unsigned[2..];
+        match hex.find(&['p', 'P'][..]) {
+            Some(index) => (&hex[..index], Some(&hex[index + 1..]), 16),
+            None => return false
+        }
+    } else {
+        match unsigned.find(&['e', 'E'][..]) {
+            Some(index) => (&unsigned[..index], Some(&unsigned[index + 1..]), 10),
+            None => (unsigned, None, 10)
+        }
+    };
+
+    let mut parts = mantissa.splitn(2, '.');
+    let whole = parts.next().unwrap_or("");
+    let fraction = parts.next().unwrap_or("");
+    let all_digits = |digits: &str| digits.chars().all(|c| c.is_digit(radix));
+    if (whole.is_empty() && fraction.is_empty()) || !all_digits(whole) || !all_digits(fraction) {
+        return false;
+    }
+
+    match exponent {
+        Some(exponent) => {
+            let digits = strip_sign(exponent);
+            !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+        },
+        None => true
+    }
+}
+
+fn strip_sign(text: &str) -> &str {
+    match text.chars().next() {
+        Some('+') | Some('-') => &text[1..],
+        _ => text
     }
 }
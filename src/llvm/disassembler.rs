@@ -0,0 +1,104 @@
+use std::ffi::CStr;
+use std::ptr;
+
+use libc::c_char;
+
+use llvm_sys::disassembler::{LLVMDisasmContextRef, LLVMCreateDisasmCPUFeatures, LLVMDisasmDispose, LLVMDisasmInstruction};
+use llvm_sys::disassembler::{LLVMSetDisasmOptions, LLVMDisassembler_Option_AsmPrinterVariant};
+
+use opaque::Opaque;
+use owned::{Owned, DropInPlace};
+
+use llvm::{TargetsInitialized, AsmDialect};
+
+/// Decodes machine code back into assembly text.
+pub struct Disassembler {
+    _opaque: Opaque
+}
+
+impl DropInPlace for Disassembler {
+    unsafe fn drop_in_place(&mut self) {
+        LLVMDisasmDispose(self.as_raw());
+    }
+}
+
+impl Disassembler {
+    /// Creates a disassembler for the given triple, CPU and features. Returns `None` if the
+    /// target is unknown, has no disassembler, or does not support the requested dialect. Only x86 targets
+    /// support `Intel`.
+    pub fn new(_init: TargetsInitialized, triple: &CStr, cpu: &CStr, features: &CStr, dialect: AsmDialect) -> Option<Owned<Disassembler>> {
+        unsafe {
+            let raw = LLVMCreateDisasmCPUFeatures(triple.as_ptr(), cpu.as_ptr(), features.as_ptr(), ptr::null_mut(), 0, None, None);
+            if raw.is_null() {
+                return None;
+            }
+            let disassembler: Owned<Disassembler> = Owned::from_raw(raw as *mut Disassembler);
+
+            // Every target prints its normal syntax by default, and the printer variant option
+            // switches x86 to Intel syntax. Targets without a second variant reject the option.
+            if dialect == AsmDialect::Intel && LLVMSetDisasmOptions(raw, LLVMDisassembler_Option_AsmPrinterVariant) == 0 {
+                return None;
+            }
+
+            Some(disassembler)
+        }
+    }
+
+    /// Disassembles `bytes`, treating them as if they were loaded at `address`. Iteration stops at
+    /// the first bytes that do not decode to an instruction.
+    pub fn disassemble<'a>(&'a mut self, bytes: &'a [u8], address: u64) -> Instructions<'a> {
+        Instructions {
+            disassembler: self,
+            bytes,
+            address,
+            offset: 0
+        }
+    }
+
+    pub fn as_raw(&self) -> LLVMDisasmContextRef {
+        self as *const Disassembler as LLVMDisasmContextRef
+    }
+}
+
+pub struct Instructions<'a> {
+    disassembler: &'a mut Disassembler,
+    bytes: &'a [u8],
+    address: u64,
+    offset: usize
+}
+
+impl<'a> Iterator for Instructions<'a> {
+    /// The offset of the instruction in the input, its length in bytes, and its text.
+    type Item = (usize, usize, String);
+
+    fn next(&mut self) -> Option<(usize, usize, String)> {
+        let remaining = &self.bytes[self.offset..];
+        if remaining.is_empty() {
+            return None;
+        }
+
+        // LLVM truncates the text to fit rather than overflowing, and even the longest x86
+        // instructions with every prefix and a full memory operand print in well under 256 bytes.
+        let mut text = [0 as c_char; 256];
+        let length = unsafe {
+            LLVMDisasmInstruction(
+                self.disassembler.as_raw(),
+                remaining.as_ptr() as *mut u8,
+                remaining.len() as u64,
+                self.address + self.offset as u64,
+                text.as_mut_ptr(),
+                text.len()
+            )
+        };
+        if length == 0 {
+            // Don't keep retrying the same bytes
+            self.offset = self.bytes.len();
+            return None;
+        }
+
+        let offset = self.offset;
+        self.offset += length;
+        let text = unsafe { CStr::from_ptr(text.as_ptr()) };
+        Some((offset, length, text.to_string_lossy().trim().to_owned()))
+    }
+}
@@ -1,32 +1,171 @@
 use std::ffi::CStr;
 use std::marker::PhantomData;
+use std::ptr;
 
 use llvm_sys::prelude::*;
 use llvm_sys::core::*;
+use llvm_sys::comdat::{LLVMGetComdat, LLVMSetComdat};
 use llvm_sys::analysis::*;
 
 use id::{Id, IdRef};
 use inheritance::{upcast, downcast_unchecked, DerivesFrom};
 use opaque::Opaque;
 
-use llvm::{Context, BasicBlock, Label, Value, Type, FunctionType, PointerType};
+use llvm::{Context, BasicBlock, Label, Value, Type, FunctionType, PointerType, Comdat, CallConv};
+use llvm::global::{Linkage, Visibility, DLLStorageClass};
+use llvm::signature::{Dynamic, ArgValues, ParamValues};
 
-pub struct Function<'cid, 'mid> {
+pub struct Function<'cid, 'mid, Args: ?Sized = Dynamic, Ret: ?Sized = Dynamic> {
     _context_id: IdRef<'cid>,
     _module_id: IdRef<'mid>,
+    _signature: PhantomData<(PhantomData<Args>, PhantomData<Ret>)>,
     _opaque: Opaque
 }
 
-impl<'cid, 'mid> Function<'cid, 'mid> {
-    pub fn label<'function>(&'function self) -> &'function FunctionLabel<'cid, 'mid> {
+impl<'cid, 'mid, Args: ?Sized, Ret: ?Sized> Function<'cid, 'mid, Args, Ret> {
+    pub fn label<'function>(&'function self) -> &'function FunctionLabel<'cid, 'mid, Args, Ret> {
         unsafe {
-            &*(self.as_raw() as *mut FunctionLabel)
+            &*(self.as_raw() as *mut FunctionLabel<Args, Ret>)
         }
     }
 
-    pub fn builder<'fid, 'function>(&'function mut self, _id: Id<'fid>) -> &'function mut FunctionBuilder<'cid, 'mid, 'fid, 'function> {
+    pub fn builder<'fid, 'function>(&'function mut self, _id: Id<'fid>) -> &'function mut FunctionBuilder<'cid, 'mid, 'fid, 'function, Args, Ret> {
         unsafe {
-            &mut *(self.as_raw() as *mut FunctionBuilder)
+            &mut *(self.as_raw() as *mut FunctionBuilder<Args, Ret>)
+        }
+    }
+
+    pub fn linkage(&self) -> Linkage {
+        unsafe {
+            Linkage::from_raw(LLVMGetLinkage(self.as_raw()))
+        }
+    }
+
+    pub fn set_linkage(&mut self, linkage: Linkage) {
+        unsafe {
+            LLVMSetLinkage(self.as_raw(), linkage.as_raw());
+        }
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        unsafe {
+            Visibility::from_raw(LLVMGetVisibility(self.as_raw()))
+        }
+    }
+
+    pub fn set_visibility(&mut self, visibility: Visibility) {
+        unsafe {
+            LLVMSetVisibility(self.as_raw(), visibility.as_raw());
+        }
+    }
+
+    pub fn dll_storage_class(&self) -> DLLStorageClass {
+        unsafe {
+            DLLStorageClass::from_raw(LLVMGetDLLStorageClass(self.as_raw()))
+        }
+    }
+
+    pub fn set_dll_storage_class(&mut self, class: DLLStorageClass) {
+        unsafe {
+            LLVMSetDLLStorageClass(self.as_raw(), class.as_raw());
+        }
+    }
+
+    pub fn call_conv(&self) -> CallConv {
+        unsafe {
+            CallConv::from_raw(LLVMGetFunctionCallConv(self.as_raw()))
+        }
+    }
+
+    pub fn set_call_conv(&mut self, call_conv: CallConv) {
+        unsafe {
+            LLVMSetFunctionCallConv(self.as_raw(), call_conv.as_raw());
+        }
+    }
+
+    pub fn comdat(&self) -> Option<&Comdat<'mid>> {
+        unsafe {
+            let comdat = LLVMGetComdat(self.as_raw());
+            if comdat.is_null() {
+                None
+            } else {
+                Some(&*(comdat as *mut Comdat))
+            }
+        }
+    }
+
+    pub fn set_comdat(&mut self, comdat: &Comdat<'mid>) {
+        unsafe {
+            LLVMSetComdat(self.as_raw(), comdat.as_raw());
+        }
+    }
+
+    /// Iterates over the blocks of this function in layout order, for inspecting finished code.
+    /// Use `rev` to walk them backwards.
+    ///
+    /// The blocks are branded with `'static` in place of a function id. No `FunctionBuilder` can
+    /// have that id, so the blocks and their instructions can be read but never used to build code.
+    ///
+    /// ```compile_fail
+    /// # use std::ffi::CString;
+    /// # use llvm_safe::id;
+    /// # use llvm_safe::inheritance::upcast;
+    /// # use llvm_safe::llvm::{Context, Module, Builder, Type, I32};
+    /// id::with2(|context_id, module_id| {
+    ///     let context = Context::new(context_id);
+    ///     let name = CString::new("function").unwrap();
+    ///     let mut module = Module::new(module_id, &name, &context);
+    ///     let module_builder = module.builder();
+    ///     let mut builder = Builder::new(&context);
+    ///
+    ///     let function_ty = Type::typed_function::<(I32,), I32>(&context);
+    ///     let first = module_builder.add_function(&name, function_ty);
+    ///     id::with(|function_id| {
+    ///         let function_builder = first.builder(function_id);
+    ///         let (arg,) = function_builder.typed_params();
+    ///         let (_, entry) = function_builder.append_basic_block(&name, &context);
+    ///         builder.position_at_end(entry).ret(upcast(arg));
+    ///     });
+    ///
+    ///     let second = module_builder.add_function(&name, function_ty);
+    ///     let returned = first.last_basic_block().unwrap().first_instruction().unwrap();
+    ///     id::with(|function_id| {
+    ///         let function_builder = second.builder(function_id);
+    ///         let (_, entry) = function_builder.append_basic_block(&name, &context);
+    ///         builder.position_at_end(entry).ret(returned.as_value());
+    ///     });
+    /// });
+    /// ```
+    pub fn basic_blocks<'function>(&'function self) -> BasicBlocks<'cid, 'mid, 'function> {
+        unsafe {
+            BasicBlocks {
+                _function: PhantomData,
+                front: LLVMGetFirstBasicBlock(self.as_raw()),
+                back: LLVMGetLastBasicBlock(self.as_raw())
+            }
+        }
+    }
+
+    /// The entry block, which is the first block of the function.
+    pub fn first_basic_block(&self) -> Option<&BasicBlock<'cid, 'mid, 'static>> {
+        unsafe {
+            let block = LLVMGetFirstBasicBlock(self.as_raw());
+            if block.is_null() {
+                None
+            } else {
+                Some(&*(block as *mut BasicBlock))
+            }
+        }
+    }
+
+    pub fn last_basic_block(&self) -> Option<&BasicBlock<'cid, 'mid, 'static>> {
+        unsafe {
+            let block = LLVMGetLastBasicBlock(self.as_raw());
+            if block.is_null() {
+                None
+            } else {
+                Some(&*(block as *mut BasicBlock))
+            }
         }
     }
 
@@ -43,17 +182,17 @@ impl<'cid, 'mid> Function<'cid, 'mid> {
     }
 
     pub fn as_raw(&self) -> LLVMValueRef {
-        self as *const Function as *mut Function as LLVMValueRef
+        self as *const Function<Args, Ret> as *mut Function<Args, Ret> as LLVMValueRef
     }
 }
 
-pub struct FunctionBuilder<'cid: 'function, 'mid: 'function, 'fid, 'function> {
-    _inner: PhantomData<&'function mut Function<'cid, 'mid>>,
+pub struct FunctionBuilder<'cid: 'function, 'mid: 'function, 'fid, 'function, Args: ?Sized + 'function = Dynamic, Ret: ?Sized + 'function = Dynamic> {
+    _inner: PhantomData<&'function mut Function<'cid, 'mid, Args, Ret>>,
     _id: Id<'fid>,
     _opaque: Opaque
 }
 
-impl<'cid, 'mid, 'fid, 'function> FunctionBuilder<'cid, 'mid, 'fid, 'function> {
+impl<'cid, 'mid, 'fid, 'function, Args: ?Sized, Ret: ?Sized> FunctionBuilder<'cid, 'mid, 'fid, 'function, Args, Ret> {
     pub fn append_basic_block(&mut self, name: &CStr, context: &Context<'cid>) -> (&'function Label<'fid>, &'function mut BasicBlock<'cid, 'mid, 'fid>) {
         unsafe {
             let bb_ref = LLVMAppendBasicBlockInContext(context.as_raw(), self.as_raw(), name.as_ptr());
@@ -70,8 +209,53 @@ impl<'cid, 'mid, 'fid, 'function> FunctionBuilder<'cid, 'mid, 'fid, 'function> {
         }
     }
 
+    /// Iterates over the blocks of this function in layout order. Use `rev` to walk them
+    /// backwards.
+    ///
+    /// The blocks are identified by their `Label`s, since the `BasicBlock`s themselves are only
+    /// reachable through the unique references handed out by `append_basic_block`.
+    pub fn basic_blocks(&self) -> FunctionBlocks<'fid, 'function> {
+        unsafe {
+            FunctionBlocks {
+                _function: PhantomData,
+                front: LLVMGetFirstBasicBlock(self.as_raw()),
+                back: LLVMGetLastBasicBlock(self.as_raw())
+            }
+        }
+    }
+
+    /// The label of the entry block, which is the first block of the function.
+    pub fn first_basic_block(&self) -> Option<&'function Label<'fid>> {
+        unsafe {
+            let block = LLVMGetFirstBasicBlock(self.as_raw());
+            if block.is_null() {
+                None
+            } else {
+                Some(&*(block as *mut Label))
+            }
+        }
+    }
+
+    pub fn last_basic_block(&self) -> Option<&'function Label<'fid>> {
+        unsafe {
+            let block = LLVMGetLastBasicBlock(self.as_raw());
+            if block.is_null() {
+                None
+            } else {
+                Some(&*(block as *mut Label))
+            }
+        }
+    }
+
+    /// Returns the parameters of a statically typed function as a tuple of typed values.
+    pub fn typed_params(&self) -> <Args as ArgValues<'function, 'cid, 'mid, 'fid>>::Values where Args: ParamValues<'function, 'cid, 'mid, 'fid> {
+        unsafe {
+            Args::from_function(self.as_raw())
+        }
+    }
+
     pub fn as_raw(&self) -> LLVMValueRef {
-        self as *const FunctionBuilder as *mut FunctionBuilder as LLVMValueRef
+        self as *const FunctionBuilder<Args, Ret> as *mut FunctionBuilder<Args, Ret> as LLVMValueRef
     }
 }
 
@@ -98,28 +282,131 @@ impl<'cid: 'function, 'mid: 'function, 'fid: 'function, 'function> Iterator for
     }
 }
 
-pub struct FunctionLabel<'cid, 'mid> {
+pub struct FunctionBlocks<'fid: 'function, 'function> {
+    _function: PhantomData<&'function Label<'fid>>,
+    front: LLVMBasicBlockRef,
+    back: LLVMBasicBlockRef
+}
+
+impl<'fid: 'function, 'function> Iterator for FunctionBlocks<'fid, 'function> {
+    type Item = &'function Label<'fid>;
+
+    fn next(&mut self) -> Option<&'function Label<'fid>> {
+        if self.front.is_null() {
+            None
+        } else {
+            unsafe {
+                let ret = Some(&*(self.front as *const Label));
+                if self.front == self.back {
+                    self.front = ptr::null_mut();
+                    self.back = ptr::null_mut();
+                } else {
+                    self.front = LLVMGetNextBasicBlock(self.front);
+                }
+                ret
+            }
+        }
+    }
+}
+
+impl<'fid: 'function, 'function> DoubleEndedIterator for FunctionBlocks<'fid, 'function> {
+    fn next_back(&mut self) -> Option<&'function Label<'fid>> {
+        if self.back.is_null() {
+            None
+        } else {
+            unsafe {
+                let ret = Some(&*(self.back as *const Label));
+                if self.front == self.back {
+                    self.front = ptr::null_mut();
+                    self.back = ptr::null_mut();
+                } else {
+                    self.back = LLVMGetPreviousBasicBlock(self.back);
+                }
+                ret
+            }
+        }
+    }
+}
+
+pub struct BasicBlocks<'cid: 'function, 'mid: 'function, 'function> {
+    _function: PhantomData<&'function Function<'cid, 'mid>>,
+    front: LLVMBasicBlockRef,
+    back: LLVMBasicBlockRef
+}
+
+impl<'cid: 'function, 'mid: 'function, 'function> Iterator for BasicBlocks<'cid, 'mid, 'function> {
+    type Item = &'function BasicBlock<'cid, 'mid, 'static>;
+
+    fn next(&mut self) -> Option<&'function BasicBlock<'cid, 'mid, 'static>> {
+        if self.front.is_null() {
+            None
+        } else {
+            unsafe {
+                let ret = Some(&*(self.front as *const BasicBlock));
+                if self.front == self.back {
+                    self.front = ptr::null_mut();
+                    self.back = ptr::null_mut();
+                } else {
+                    self.front = LLVMGetNextBasicBlock(self.front);
+                }
+                ret
+            }
+        }
+    }
+}
+
+impl<'cid: 'function, 'mid: 'function, 'function> DoubleEndedIterator for BasicBlocks<'cid, 'mid, 'function> {
+    fn next_back(&mut self) -> Option<&'function BasicBlock<'cid, 'mid, 'static>> {
+        if self.back.is_null() {
+            None
+        } else {
+            unsafe {
+                let ret = Some(&*(self.back as *const BasicBlock));
+                if self.front == self.back {
+                    self.front = ptr::null_mut();
+                    self.back = ptr::null_mut();
+                } else {
+                    self.back = LLVMGetPreviousBasicBlock(self.back);
+                }
+                ret
+            }
+        }
+    }
+}
+
+pub struct FunctionLabel<'cid, 'mid, Args: ?Sized = Dynamic, Ret: ?Sized = Dynamic> {
     _context_id: IdRef<'cid>,
     _module_id: IdRef<'mid>,
+    _signature: PhantomData<(PhantomData<Args>, PhantomData<Ret>)>,
     _opaque: Opaque
 }
-unsafe impl<'cid, 'mid> DerivesFrom<FunctionLabel<'cid, 'mid>> for FunctionLabel<'cid, 'mid> { }
-unsafe impl<'cid, 'mid, 'fid, General: ?Sized> DerivesFrom<General> for FunctionLabel<'cid, 'mid> where Value<'cid, 'mid, 'fid, PointerType<'cid, FunctionType<'cid>>>: DerivesFrom<General> { }
+unsafe impl<'cid, 'mid, Args: ?Sized, Ret: ?Sized> DerivesFrom<FunctionLabel<'cid, 'mid, Args, Ret>> for FunctionLabel<'cid, 'mid, Args, Ret> { }
+unsafe impl<'cid, 'mid, 'fid, General: ?Sized, Args: ?Sized, Ret: ?Sized> DerivesFrom<General> for FunctionLabel<'cid, 'mid, Args, Ret> where Value<'cid, 'mid, 'fid, PointerType<'cid, FunctionType<'cid, Args, Ret>>>: DerivesFrom<General> { }
 
-impl<'cid, 'mid> FunctionLabel<'cid, 'mid> {
+impl<'cid, 'mid, Args: ?Sized, Ret: ?Sized> FunctionLabel<'cid, 'mid, Args, Ret> {
     pub fn num_args(&self) -> usize {
         unsafe {
             LLVMCountParams(self.as_raw()) as usize
         }
     }
 
-    pub fn function_type(&self) -> &FunctionType<'cid> {
+    pub fn function_type(&self) -> &FunctionType<'cid, Args, Ret> {
         unsafe {
             // FIXME: return the pointer type?
-            downcast_unchecked::<PointerType<FunctionType>,_>(Type::of_value(self.as_value())).pointee_ty()
+            downcast_unchecked::<PointerType<FunctionType<Args, Ret>>,_>(Type::of_value(self.as_value())).pointee_ty()
         }
     }
 
+    pub fn as_value<'fid>(&self) -> &Value<'cid, 'mid, 'fid, PointerType<'cid, FunctionType<'cid, Args, Ret>>> {
+        upcast(self)
+    }
+
+    pub fn as_raw(&self) -> LLVMValueRef {
+        self.as_value().as_raw()
+    }
+}
+
+impl<'cid, 'mid> FunctionLabel<'cid, 'mid> {
     // TODO: Restrict to FunctionType?
     pub fn downcast_value<'a, 'fid, Ty: ?Sized>(value: &'a Value<'cid, 'mid, 'fid, Ty>) -> Result<&'a FunctionLabel<'cid, 'mid>, ()> {
         unsafe {
@@ -131,12 +418,4 @@ impl<'cid, 'mid> FunctionLabel<'cid, 'mid> {
             }
         }
     }
-
-    pub fn as_value<'fid>(&self) -> &Value<'cid, 'mid, 'fid, PointerType<'cid, FunctionType<'cid>>> {
-        upcast(self)
-    }
-
-    pub fn as_raw(&self) -> LLVMValueRef {
-        self.as_value().as_raw()
-    }
 }
@@ -1,13 +1,210 @@
-use llvm_sys::prelude::*;
-use llvm_sys::core::{LLVMSetInitializer, LLVMIsAGlobalVariable};
-
+use std::ffi::CStr;
 use std::marker::PhantomData;
 
+use libc::{c_int, c_uint};
+
+use llvm_sys::prelude::*;
+use llvm_sys::core::*;
+use llvm_sys::comdat::{LLVMGetComdat, LLVMSetComdat};
+use llvm_sys::{LLVMLinkage, LLVMVisibility, LLVMDLLStorageClass, LLVMUnnamedAddr, LLVMThreadLocalMode};
+
 use id::IdRef;
 use inheritance::DerivesFrom;
 use opaque::Opaque;
 
-use llvm::{Constant, Value, PointerType};
+use llvm::{Constant, InModule, UsableIn, Value, PointerType, Comdat};
+
+/// The [linkage type][langref] of a global value.
+///
+/// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#linkage-types
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Linkage {
+    External,
+    AvailableExternally,
+    LinkOnceAny,
+    LinkOnceODR,
+    WeakAny,
+    WeakODR,
+    Appending,
+    Internal,
+    Private,
+    ExternalWeak,
+    Common,
+    // Obsolete linkage types, kept so that every `LLVMLinkage` has a variant.
+    LinkOnceODRAutoHide,
+    DLLImport,
+    DLLExport,
+    Ghost,
+    LinkerPrivate,
+    LinkerPrivateWeak
+}
+
+impl Linkage {
+    pub fn from_raw(raw: LLVMLinkage) -> Linkage {
+        match raw {
+            LLVMLinkage::LLVMExternalLinkage => Linkage::External,
+            LLVMLinkage::LLVMAvailableExternallyLinkage => Linkage::AvailableExternally,
+            LLVMLinkage::LLVMLinkOnceAnyLinkage => Linkage::LinkOnceAny,
+            LLVMLinkage::LLVMLinkOnceODRLinkage => Linkage::LinkOnceODR,
+            LLVMLinkage::LLVMWeakAnyLinkage => Linkage::WeakAny,
+            LLVMLinkage::LLVMWeakODRLinkage => Linkage::WeakODR,
+            LLVMLinkage::LLVMAppendingLinkage => Linkage::Appending,
+            LLVMLinkage::LLVMInternalLinkage => Linkage::Internal,
+            LLVMLinkage::LLVMPrivateLinkage => Linkage::Private,
+            LLVMLinkage::LLVMExternalWeakLinkage => Linkage::ExternalWeak,
+            LLVMLinkage::LLVMCommonLinkage => Linkage::Common,
+            LLVMLinkage::LLVMLinkOnceODRAutoHideLinkage => Linkage::LinkOnceODRAutoHide,
+            LLVMLinkage::LLVMDLLImportLinkage => Linkage::DLLImport,
+            LLVMLinkage::LLVMDLLExportLinkage => Linkage::DLLExport,
+            LLVMLinkage::LLVMGhostLinkage => Linkage::Ghost,
+            LLVMLinkage::LLVMLinkerPrivateLinkage => Linkage::LinkerPrivate,
+            LLVMLinkage::LLVMLinkerPrivateWeakLinkage => Linkage::LinkerPrivateWeak
+        }
+    }
+
+    pub fn as_raw(self) -> LLVMLinkage {
+        match self {
+            Linkage::External => LLVMLinkage::LLVMExternalLinkage,
+            Linkage::AvailableExternally => LLVMLinkage::LLVMAvailableExternallyLinkage,
+            Linkage::LinkOnceAny => LLVMLinkage::LLVMLinkOnceAnyLinkage,
+            Linkage::LinkOnceODR => LLVMLinkage::LLVMLinkOnceODRLinkage,
+            Linkage::WeakAny => LLVMLinkage::LLVMWeakAnyLinkage,
+            Linkage::WeakODR => LLVMLinkage::LLVMWeakODRLinkage,
+            Linkage::Appending => LLVMLinkage::LLVMAppendingLinkage,
+            Linkage::Internal => LLVMLinkage::LLVMInternalLinkage,
+            Linkage::Private => LLVMLinkage::LLVMPrivateLinkage,
+            Linkage::ExternalWeak => LLVMLinkage::LLVMExternalWeakLinkage,
+            Linkage::Common => LLVMLinkage::LLVMCommonLinkage,
+            Linkage::LinkOnceODRAutoHide => LLVMLinkage::LLVMLinkOnceODRAutoHideLinkage,
+            Linkage::DLLImport => LLVMLinkage::LLVMDLLImportLinkage,
+            Linkage::DLLExport => LLVMLinkage::LLVMDLLExportLinkage,
+            Linkage::Ghost => LLVMLinkage::LLVMGhostLinkage,
+            Linkage::LinkerPrivate => LLVMLinkage::LLVMLinkerPrivateLinkage,
+            Linkage::LinkerPrivateWeak => LLVMLinkage::LLVMLinkerPrivateWeakLinkage
+        }
+    }
+}
+
+/// The [visibility style][langref] of a global value.
+///
+/// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#visibility-styles
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Visibility {
+    Default,
+    Hidden,
+    Protected
+}
+
+impl Visibility {
+    pub fn from_raw(raw: LLVMVisibility) -> Visibility {
+        match raw {
+            LLVMVisibility::LLVMDefaultVisibility => Visibility::Default,
+            LLVMVisibility::LLVMHiddenVisibility => Visibility::Hidden,
+            LLVMVisibility::LLVMProtectedVisibility => Visibility::Protected
+        }
+    }
+
+    pub fn as_raw(self) -> LLVMVisibility {
+        match self {
+            Visibility::Default => LLVMVisibility::LLVMDefaultVisibility,
+            Visibility::Hidden => LLVMVisibility::LLVMHiddenVisibility,
+            Visibility::Protected => LLVMVisibility::LLVMProtectedVisibility
+        }
+    }
+}
+
+/// The [DLL storage class][langref] of a global value.
+///
+/// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#dll-storage-classes
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DLLStorageClass {
+    Default,
+    DLLImport,
+    DLLExport
+}
+
+impl DLLStorageClass {
+    pub fn from_raw(raw: LLVMDLLStorageClass) -> DLLStorageClass {
+        match raw {
+            LLVMDLLStorageClass::LLVMDefaultStorageClass => DLLStorageClass::Default,
+            LLVMDLLStorageClass::LLVMDLLImportStorageClass => DLLStorageClass::DLLImport,
+            LLVMDLLStorageClass::LLVMDLLExportStorageClass => DLLStorageClass::DLLExport
+        }
+    }
+
+    pub fn as_raw(self) -> LLVMDLLStorageClass {
+        match self {
+            DLLStorageClass::Default => LLVMDLLStorageClass::LLVMDefaultStorageClass,
+            DLLStorageClass::DLLImport => LLVMDLLStorageClass::LLVMDLLImportStorageClass,
+            DLLStorageClass::DLLExport => LLVMDLLStorageClass::LLVMDLLExportStorageClass
+        }
+    }
+}
+
+/// Whether the address of a global value is significant, as marked by [`unnamed_addr`][langref].
+///
+/// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#global-variables
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UnnamedAddr {
+    /// The address is significant.
+    None,
+    /// The address is insignificant within the module (`local_unnamed_addr`).
+    Local,
+    /// The address is insignificant everywhere (`unnamed_addr`).
+    Global
+}
+
+impl UnnamedAddr {
+    pub fn from_raw(raw: LLVMUnnamedAddr) -> UnnamedAddr {
+        match raw {
+            LLVMUnnamedAddr::LLVMNoUnnamedAddr => UnnamedAddr::None,
+            LLVMUnnamedAddr::LLVMLocalUnnamedAddr => UnnamedAddr::Local,
+            LLVMUnnamedAddr::LLVMGlobalUnnamedAddr => UnnamedAddr::Global
+        }
+    }
+
+    pub fn as_raw(self) -> LLVMUnnamedAddr {
+        match self {
+            UnnamedAddr::None => LLVMUnnamedAddr::LLVMNoUnnamedAddr,
+            UnnamedAddr::Local => LLVMUnnamedAddr::LLVMLocalUnnamedAddr,
+            UnnamedAddr::Global => LLVMUnnamedAddr::LLVMGlobalUnnamedAddr
+        }
+    }
+}
+
+/// The [thread local storage model][langref] of a global variable.
+///
+/// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#thread-local-storage-models
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ThreadLocalMode {
+    NotThreadLocal,
+    GeneralDynamic,
+    LocalDynamic,
+    InitialExec,
+    LocalExec
+}
+
+impl ThreadLocalMode {
+    pub fn from_raw(raw: LLVMThreadLocalMode) -> ThreadLocalMode {
+        match raw {
+            LLVMThreadLocalMode::LLVMNotThreadLocal => ThreadLocalMode::NotThreadLocal,
+            LLVMThreadLocalMode::LLVMGeneralDynamicTLSModel => ThreadLocalMode::GeneralDynamic,
+            LLVMThreadLocalMode::LLVMLocalDynamicTLSModel => ThreadLocalMode::LocalDynamic,
+            LLVMThreadLocalMode::LLVMInitialExecTLSModel => ThreadLocalMode::InitialExec,
+            LLVMThreadLocalMode::LLVMLocalExecTLSModel => ThreadLocalMode::LocalExec
+        }
+    }
+
+    pub fn as_raw(self) -> LLVMThreadLocalMode {
+        match self {
+            ThreadLocalMode::NotThreadLocal => LLVMThreadLocalMode::LLVMNotThreadLocal,
+            ThreadLocalMode::GeneralDynamic => LLVMThreadLocalMode::LLVMGeneralDynamicTLSModel,
+            ThreadLocalMode::LocalDynamic => LLVMThreadLocalMode::LLVMLocalDynamicTLSModel,
+            ThreadLocalMode::InitialExec => LLVMThreadLocalMode::LLVMInitialExecTLSModel,
+            ThreadLocalMode::LocalExec => LLVMThreadLocalMode::LLVMLocalExecTLSModel
+        }
+    }
+}
 
 pub struct Global<'cid, 'mid, Ty: ?Sized> {
     _context_id: IdRef<'cid>,
@@ -19,12 +216,119 @@ unsafe impl<'cid, 'mid, SpecificTy: DerivesFrom<GeneralTy> + ?Sized, GeneralTy:
 unsafe impl<'cid, 'mid, 'fid, General: ?Sized, Ty: ?Sized> DerivesFrom<General> for Global<'cid, 'mid, Ty> where Value<'cid, 'mid, 'fid, PointerType<'cid, Ty>>: DerivesFrom<General> { }
 
 impl<'cid, 'mid, Ty: ?Sized> Global<'cid, 'mid, Ty> {
-    pub fn set_initializer(&mut self, value: &Constant<'cid, Ty>) {
+    pub fn set_initializer<Module: UsableIn<'mid>>(&mut self, value: &Constant<'cid, Ty, Module>) {
         unsafe {
             LLVMSetInitializer(self.as_raw(), value.as_raw());
         }
     }
 
+    pub fn is_constant(&self) -> bool {
+        unsafe {
+            LLVMIsGlobalConstant(self.as_raw()) != 0
+        }
+    }
+
+    pub fn set_constant(&mut self, is_constant: bool) {
+        unsafe {
+            LLVMSetGlobalConstant(self.as_raw(), is_constant as c_int);
+        }
+    }
+
+    pub fn is_declaration(&self) -> bool {
+        unsafe {
+            LLVMIsDeclaration(self.as_raw()) != 0
+        }
+    }
+
+    pub fn linkage(&self) -> Linkage {
+        unsafe {
+            Linkage::from_raw(LLVMGetLinkage(self.as_raw()))
+        }
+    }
+
+    pub fn set_linkage(&mut self, linkage: Linkage) {
+        unsafe {
+            LLVMSetLinkage(self.as_raw(), linkage.as_raw());
+        }
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        unsafe {
+            Visibility::from_raw(LLVMGetVisibility(self.as_raw()))
+        }
+    }
+
+    pub fn set_visibility(&mut self, visibility: Visibility) {
+        unsafe {
+            LLVMSetVisibility(self.as_raw(), visibility.as_raw());
+        }
+    }
+
+    pub fn dll_storage_class(&self) -> DLLStorageClass {
+        unsafe {
+            DLLStorageClass::from_raw(LLVMGetDLLStorageClass(self.as_raw()))
+        }
+    }
+
+    pub fn set_dll_storage_class(&mut self, class: DLLStorageClass) {
+        unsafe {
+            LLVMSetDLLStorageClass(self.as_raw(), class.as_raw());
+        }
+    }
+
+    pub fn unnamed_addr(&self) -> UnnamedAddr {
+        unsafe {
+            UnnamedAddr::from_raw(LLVMGetUnnamedAddress(self.as_raw()))
+        }
+    }
+
+    pub fn set_unnamed_addr(&mut self, unnamed_addr: UnnamedAddr) {
+        unsafe {
+            LLVMSetUnnamedAddress(self.as_raw(), unnamed_addr.as_raw());
+        }
+    }
+
+    pub fn comdat(&self) -> Option<&Comdat<'mid>> {
+        unsafe {
+            let comdat = LLVMGetComdat(self.as_raw());
+            if comdat.is_null() {
+                None
+            } else {
+                Some(&*(comdat as *mut Comdat))
+            }
+        }
+    }
+
+    pub fn set_comdat(&mut self, comdat: &Comdat<'mid>) {
+        unsafe {
+            LLVMSetComdat(self.as_raw(), comdat.as_raw());
+        }
+    }
+
+    pub fn set_section(&mut self, section: &CStr) {
+        unsafe {
+            LLVMSetSection(self.as_raw(), section.as_ptr());
+        }
+    }
+
+    pub fn set_alignment(&mut self, alignment: c_uint) {
+        unsafe {
+            LLVMSetAlignment(self.as_raw(), alignment);
+        }
+    }
+
+    pub fn thread_local_mode(&self) -> ThreadLocalMode {
+        unsafe {
+            ThreadLocalMode::from_raw(LLVMGetThreadLocalMode(self.as_raw()))
+        }
+    }
+
+    pub fn set_thread_local_mode(&mut self, mode: ThreadLocalMode) {
+        unsafe {
+            LLVMSetThreadLocalMode(self.as_raw(), mode.as_raw());
+        }
+    }
+
     pub fn downcast_value<'a, 'fid>(value: &'a Value<'cid, 'mid, 'fid, PointerType<'cid, Ty>>) -> Result<&'a Global<'cid, 'mid, Ty>, ()> {
         unsafe {
             let ret = LLVMIsAGlobalVariable(value.as_raw());
@@ -42,6 +346,32 @@ impl<'cid, 'mid, Ty: ?Sized> Global<'cid, 'mid, Ty> {
         }
     }
 
+    /// Returns the address of this global as a constant, for use in initializers and constant
+    /// expressions. The constant is branded with the module, so it can only be used in this module.
+    ///
+    /// ```compile_fail
+    /// # use std::ffi::CString;
+    /// # use llvm_safe::id;
+    /// # use llvm_safe::llvm::{Context, Module, Type};
+    /// id::with2(|context_id, first_id| id::with(|second_id| {
+    ///     let context = Context::new(context_id);
+    ///     let name = CString::new("global").unwrap();
+    ///     let mut first = Module::new(first_id, &name, &context);
+    ///     let mut second = Module::new(second_id, &name, &context);
+    ///     let first_builder = first.builder();
+    ///     let second_builder = second.builder();
+    ///
+    ///     let target = first_builder.add_global(&name, Type::i32(&context));
+    ///     let pointer = second_builder.add_global(&name, Type::pointer(Type::i32(&context), 0));
+    ///     pointer.set_initializer(target.as_constant());
+    /// }));
+    /// ```
+    pub fn as_constant(&self) -> &Constant<'cid, PointerType<'cid, Ty>, InModule<'mid>> {
+        unsafe {
+            &*(self as *const _ as *const Constant<PointerType<Ty>, InModule>)
+        }
+    }
+
     pub fn as_raw(&self) -> LLVMValueRef {
         self.as_value().as_raw()
     }
@@ -1,21 +1,63 @@
-use llvm_sys::target::{LLVM_InitializeAllTargetInfos, LLVM_InitializeAllTargets, LLVM_InitializeAllTargetMCs, LLVM_InitializeAllAsmPrinters, LLVM_InitializeAllAsmParsers};
+use std::sync::Once;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-pub unsafe fn init_target_infos() {
-    LLVM_InitializeAllTargetInfos()
-}
+use llvm_sys::target::{LLVM_InitializeAllTargetInfos, LLVM_InitializeAllTargets, LLVM_InitializeAllTargetMCs};
+use llvm_sys::target::{LLVM_InitializeAllAsmPrinters, LLVM_InitializeAllAsmParsers, LLVM_InitializeAllDisassemblers};
+use llvm_sys::target::{LLVM_InitializeNativeTarget, LLVM_InitializeNativeAsmPrinter, LLVM_InitializeNativeAsmParser, LLVM_InitializeNativeDisassembler};
 
-pub unsafe fn init_targets() {
-    LLVM_InitializeAllTargets()
+/// Proof that LLVM's target backends have been initialized. Looking up targets requires one of
+/// these, since otherwise the registry is silently empty.
+///
+/// LLVM's initialization functions are not thread safe, even against each other, so each kind of
+/// initialization runs at most once per process, and `all` finishes native initialization before
+/// starting its own so that the two never overlap. Getting a token is cheap after the first time.
+#[derive(Copy, Clone, Debug)]
+pub struct TargetsInitialized {
+    _private: ()
 }
 
-pub unsafe fn init_target_mcs() {
-    LLVM_InitializeAllTargetMCs()
-}
+static INIT_ALL: Once = Once::new();
+static INIT_NATIVE: Once = Once::new();
+static NATIVE_OK: AtomicBool = AtomicBool::new(false);
 
-pub unsafe fn init_asm_printers() {
-    LLVM_InitializeAllAsmPrinters()
+fn init_native() -> bool {
+    INIT_NATIVE.call_once(|| {
+        let ok = unsafe {
+            // The asm parser and disassembler are optional parts of a backend, so only the target
+            // itself and its printer are required to succeed.
+            let ok = LLVM_InitializeNativeTarget() == 0 && LLVM_InitializeNativeAsmPrinter() == 0;
+            LLVM_InitializeNativeAsmParser();
+            LLVM_InitializeNativeDisassembler();
+            ok
+        };
+        NATIVE_OK.store(ok, Ordering::Release);
+    });
+    NATIVE_OK.load(Ordering::Acquire)
 }
 
-pub unsafe fn init_asm_parsers() {
-    LLVM_InitializeAllAsmParsers()
+impl TargetsInitialized {
+    /// Initializes every backend LLVM was built with, including their assembly printers and
+    /// parsers and disassemblers.
+    pub fn all() -> TargetsInitialized {
+        init_native();
+        INIT_ALL.call_once(|| unsafe {
+            LLVM_InitializeAllTargetInfos();
+            LLVM_InitializeAllTargets();
+            LLVM_InitializeAllTargetMCs();
+            LLVM_InitializeAllAsmPrinters();
+            LLVM_InitializeAllAsmParsers();
+            LLVM_InitializeAllDisassemblers();
+        });
+        TargetsInitialized { _private: () }
+    }
+
+    /// Initializes only the host's backend, including its assembly printer and parser and
+    /// disassembler. Fails if LLVM was built without support for the host.
+    pub fn native() -> Result<TargetsInitialized, ()> {
+        if init_native() {
+            Ok(TargetsInitialized { _private: () })
+        } else {
+            Err(())
+        }
+    }
 }
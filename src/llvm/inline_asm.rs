@@ -0,0 +1,82 @@
+use llvm_sys::prelude::*;
+use llvm_sys::core::*;
+use llvm_sys::LLVMInlineAsmDialect;
+
+use std::marker::PhantomData;
+use std::ffi::CStr;
+use libc::c_char;
+
+use id::IdRef;
+use inheritance::{upcast, DerivesFrom};
+use opaque::Opaque;
+
+use llvm::{Type, FunctionType, PointerType, Value};
+use llvm::signature::Dynamic;
+
+/// The syntax of assembly text, for inline assembly and for disassembler output. Only x86
+/// targets distinguish the two; every other target only supports `ATT`, its normal syntax.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AsmDialect {
+    ATT,
+    Intel
+}
+
+impl AsmDialect {
+    pub fn from_raw(raw: LLVMInlineAsmDialect) -> AsmDialect {
+        match raw {
+            LLVMInlineAsmDialect::LLVMInlineAsmDialectATT => AsmDialect::ATT,
+            LLVMInlineAsmDialect::LLVMInlineAsmDialectIntel => AsmDialect::Intel
+        }
+    }
+
+    pub fn as_raw(self) -> LLVMInlineAsmDialect {
+        match self {
+            AsmDialect::ATT => LLVMInlineAsmDialect::LLVMInlineAsmDialectATT,
+            AsmDialect::Intel => LLVMInlineAsmDialect::LLVMInlineAsmDialectIntel
+        }
+    }
+}
+
+/// An [inline assembler expression][langref], which can be called like a function of type
+/// `FunctionType<'cid, Args, Ret>`.
+///
+/// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#inline-assembler-expressions
+pub struct InlineAsm<'cid, Args: ?Sized = Dynamic, Ret: ?Sized = Dynamic> {
+    _context: IdRef<'cid>,
+    _signature: PhantomData<(PhantomData<Args>, PhantomData<Ret>)>,
+    _opaque: Opaque
+}
+unsafe impl<'cid, 'mid, 'fid, General: ?Sized, Args: ?Sized, Ret: ?Sized> DerivesFrom<General> for InlineAsm<'cid, Args, Ret> where Value<'cid, 'mid, 'fid, PointerType<'cid, FunctionType<'cid, Args, Ret>>>: DerivesFrom<General> { }
+
+impl<'cid, Args: ?Sized, Ret: ?Sized> InlineAsm<'cid, Args, Ret> {
+    /// Creates an inline assembly expression. `constraints` describes how the arguments and
+    /// result map to registers and memory, as in `"=r,r,~{memory}"`. `side_effects` must be set
+    /// for assembly that does more than compute its result, or it may be removed or reordered.
+    /// `align_stack` requests that the stack be aligned before the assembly runs.
+    ///
+    /// Corresponds to `LLVMGetInlineAsm`.
+    pub fn new<'ctx>(fn_ty: &'ctx FunctionType<'cid, Args, Ret>, asm: &CStr, constraints: &CStr, side_effects: bool, align_stack: bool, dialect: AsmDialect) -> &'ctx InlineAsm<'cid, Args, Ret> {
+        unsafe {
+            &*(LLVMGetInlineAsm(
+                upcast::<_,Type>(fn_ty).as_raw(),
+                asm.as_ptr() as *mut c_char,
+                asm.to_bytes().len(),
+                constraints.as_ptr() as *mut c_char,
+                constraints.to_bytes().len(),
+                side_effects as LLVMBool,
+                align_stack as LLVMBool,
+                dialect.as_raw()
+            ) as *mut InlineAsm<Args, Ret>)
+        }
+    }
+
+    pub fn as_value<'mid, 'fid>(&self) -> &Value<'cid, 'mid, 'fid, PointerType<'cid, FunctionType<'cid, Args, Ret>>> {
+        unsafe {
+            &*(self as *const _ as *const Value<PointerType<FunctionType<Args, Ret>>>)
+        }
+    }
+
+    pub fn as_raw(&self) -> LLVMValueRef {
+        self.as_value().as_raw()
+    }
+}
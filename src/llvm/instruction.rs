@@ -0,0 +1,564 @@
+use llvm_sys::prelude::*;
+use llvm_sys::core::*;
+use llvm_sys::{LLVMOpcode, LLVMIntPredicate, LLVMRealPredicate};
+
+use std::ffi::CString;
+use std::marker::PhantomData;
+use std::ptr;
+use std::slice;
+use libc::c_uint;
+
+use id::IdRef;
+use inheritance::{upcast, DerivesFrom};
+use opaque::Opaque;
+
+use llvm::{BasicBlock, Label, Type, Value, ValueUse, Alloca, Call, Phi};
+
+macro_rules! opcode_impl {
+    ( $($rust_name:ident, $c_name:ident)* )  => {
+        /// The kind of operation an instruction performs, as listed in the
+        /// [instruction reference][langref].
+        ///
+        /// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#instruction-reference
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum Opcode {
+            $($rust_name,)*
+        }
+
+        impl Opcode {
+            pub fn from_raw(raw: LLVMOpcode) -> Opcode {
+                match raw {
+                    $(LLVMOpcode::$c_name => Opcode::$rust_name,)*
+                }
+            }
+
+            pub fn as_raw(self) -> LLVMOpcode {
+                match self {
+                    $(Opcode::$rust_name => LLVMOpcode::$c_name,)*
+                }
+            }
+        }
+    };
+}
+
+opcode_impl!{
+    Ret, LLVMRet
+    Br, LLVMBr
+    Switch, LLVMSwitch
+    IndirectBr, LLVMIndirectBr
+    Invoke, LLVMInvoke
+    Unreachable, LLVMUnreachable
+    FNeg, LLVMFNeg
+    Add, LLVMAdd
+    FAdd, LLVMFAdd
+    Sub, LLVMSub
+    FSub, LLVMFSub
+    Mul, LLVMMul
+    FMul, LLVMFMul
+    UDiv, LLVMUDiv
+    SDiv, LLVMSDiv
+    FDiv, LLVMFDiv
+    URem, LLVMURem
+    SRem, LLVMSRem
+    FRem, LLVMFRem
+    Shl, LLVMShl
+    LShr, LLVMLShr
+    AShr, LLVMAShr
+    And, LLVMAnd
+    Or, LLVMOr
+    Xor, LLVMXor
+    Alloca, LLVMAlloca
+    Load, LLVMLoad
+    Store, LLVMStore
+    GetElementPtr, LLVMGetElementPtr
+    Trunc, LLVMTrunc
+    ZExt, LLVMZExt
+    SExt, LLVMSExt
+    FPToUI, LLVMFPToUI
+    FPToSI, LLVMFPToSI
+    UIToFP, LLVMUIToFP
+    SIToFP, LLVMSIToFP
+    FPTrunc, LLVMFPTrunc
+    FPExt, LLVMFPExt
+    PtrToInt, LLVMPtrToInt
+    IntToPtr, LLVMIntToPtr
+    BitCast, LLVMBitCast
+    AddrSpaceCast, LLVMAddrSpaceCast
+    ICmp, LLVMICmp
+    FCmp, LLVMFCmp
+    Phi, LLVMPHI
+    Call, LLVMCall
+    Select, LLVMSelect
+    UserOp1, LLVMUserOp1
+    UserOp2, LLVMUserOp2
+    VAArg, LLVMVAArg
+    ExtractElement, LLVMExtractElement
+    InsertElement, LLVMInsertElement
+    ShuffleVector, LLVMShuffleVector
+    ExtractValue, LLVMExtractValue
+    InsertValue, LLVMInsertValue
+    Fence, LLVMFence
+    AtomicCmpXchg, LLVMAtomicCmpXchg
+    AtomicRMW, LLVMAtomicRMW
+    Resume, LLVMResume
+    LandingPad, LLVMLandingPad
+    CleanupRet, LLVMCleanupRet
+    CatchRet, LLVMCatchRet
+    CatchPad, LLVMCatchPad
+    CleanupPad, LLVMCleanupPad
+    CatchSwitch, LLVMCatchSwitch
+}
+
+/// An instruction broken down by kind, as returned by `Instruction::classify`.
+pub enum InstructionKind<'a, 'cid: 'a, 'mid: 'a, 'fid: 'a> {
+    Alloca(&'a Alloca<'cid, 'mid, 'fid, Type<'cid>>),
+    Call(&'a Call<'cid, 'mid, 'fid, Type<'cid>>),
+    Phi(&'a Phi<'cid, 'mid, 'fid, Type<'cid>>),
+    Load {
+        pointer: &'a Value<'cid, 'mid, 'fid, Type<'cid>>,
+        volatile: bool
+    },
+    Store {
+        value: &'a Value<'cid, 'mid, 'fid, Type<'cid>>,
+        pointer: &'a Value<'cid, 'mid, 'fid, Type<'cid>>,
+        volatile: bool
+    },
+    /// An unconditional `br`.
+    Branch {
+        destination: &'a Label<'fid>
+    },
+    /// A `br` with a condition.
+    CondBranch {
+        condition: &'a Value<'cid, 'mid, 'fid, Type<'cid>>,
+        then_block: &'a Label<'fid>,
+        else_block: &'a Label<'fid>
+    },
+    Return {
+        value: Option<&'a Value<'cid, 'mid, 'fid, Type<'cid>>>
+    },
+    ICmp {
+        predicate: LLVMIntPredicate,
+        lhs: &'a Value<'cid, 'mid, 'fid, Type<'cid>>,
+        rhs: &'a Value<'cid, 'mid, 'fid, Type<'cid>>
+    },
+    FCmp {
+        predicate: LLVMRealPredicate,
+        lhs: &'a Value<'cid, 'mid, 'fid, Type<'cid>>,
+        rhs: &'a Value<'cid, 'mid, 'fid, Type<'cid>>
+    },
+    /// Any other instruction, which can be inspected through its opcode and operands.
+    Other(Opcode)
+}
+
+/// An instruction of unknown kind, as found when walking existing IR.
+pub struct Instruction<'cid, 'mid, 'fid> {
+    _super: Value<'cid, 'mid, 'fid, Type<'cid>>
+}
+unsafe impl<'cid, 'mid, 'fid, General: ?Sized> DerivesFrom<General> for Instruction<'cid, 'mid, 'fid> where Value<'cid, 'mid, 'fid, Type<'cid>>: DerivesFrom<General> { }
+
+impl<'cid, 'mid, 'fid> Instruction<'cid, 'mid, 'fid> {
+    pub fn opcode(&self) -> Opcode {
+        unsafe {
+            Opcode::from_raw(LLVMGetInstructionOpcode(self.as_raw()))
+        }
+    }
+
+    pub fn num_operands(&self) -> usize {
+        unsafe {
+            LLVMGetNumOperands(self.as_raw()) as usize
+        }
+    }
+
+    /// Returns the operand at `index`, or `None` if there are not that many operands.
+    ///
+    /// Operands are untyped, and include the blocks that terminators branch to.
+    pub fn operand(&self, index: usize) -> Option<&Value<'cid, 'mid, 'fid, Type<'cid>>> {
+        if index < self.num_operands() {
+            unsafe {
+                Some(&*(LLVMGetOperand(self.as_raw(), index as c_uint) as *mut Value<Type>))
+            }
+        } else {
+            None
+        }
+    }
+
+    pub fn operands<'a>(&'a self) -> InstructionOperands<'a, 'cid, 'mid, 'fid> {
+        InstructionOperands {
+            _instruction: PhantomData,
+            inner: self.as_raw(),
+            index: 0,
+            len: self.num_operands()
+        }
+    }
+
+    /// Iterates over the places this instruction is used as an operand. Unlike constants and
+    /// globals, an instruction can only be used within its own function, so the users share its
+    /// brand.
+    pub fn uses<'a>(&'a self) -> InstructionUses<'a, 'cid, 'mid, 'fid> {
+        InstructionUses {
+            _instruction: PhantomData,
+            inner: unsafe { LLVMGetFirstUse(self.as_raw()) }
+        }
+    }
+
+    /// Determines what kind of instruction this is, returning a handle specific to that kind or
+    /// its interesting operands.
+    pub fn classify<'a>(&'a self) -> InstructionKind<'a, 'cid, 'mid, 'fid> {
+        unsafe {
+            let raw = self.as_raw();
+            let operand = |index: c_uint| &*(LLVMGetOperand(raw, index) as *mut Value<Type>);
+            let successor = |index: c_uint| &*(LLVMGetSuccessor(raw, index) as *mut Label);
+
+            if !LLVMIsAAllocaInst(raw).is_null() {
+                InstructionKind::Alloca(&*(raw as *mut Alloca<Type>))
+            } else if !LLVMIsACallInst(raw).is_null() {
+                InstructionKind::Call(&*(raw as *mut Call<Type>))
+            } else if !LLVMIsAPHINode(raw).is_null() {
+                InstructionKind::Phi(&*(raw as *mut Phi<Type>))
+            } else if !LLVMIsALoadInst(raw).is_null() {
+                InstructionKind::Load {
+                    pointer: operand(0),
+                    volatile: LLVMGetVolatile(raw) != 0
+                }
+            } else if !LLVMIsAStoreInst(raw).is_null() {
+                InstructionKind::Store {
+                    value: operand(0),
+                    pointer: operand(1),
+                    volatile: LLVMGetVolatile(raw) != 0
+                }
+            } else if !LLVMIsABranchInst(raw).is_null() {
+                if LLVMIsConditional(raw) != 0 {
+                    InstructionKind::CondBranch {
+                        condition: &*(LLVMGetCondition(raw) as *mut Value<Type>),
+                        then_block: successor(0),
+                        else_block: successor(1)
+                    }
+                } else {
+                    InstructionKind::Branch {
+                        destination: successor(0)
+                    }
+                }
+            } else if !LLVMIsAReturnInst(raw).is_null() {
+                InstructionKind::Return {
+                    value: if LLVMGetNumOperands(raw) == 0 { None } else { Some(operand(0)) }
+                }
+            } else if !LLVMIsAICmpInst(raw).is_null() {
+                InstructionKind::ICmp {
+                    predicate: LLVMGetICmpPredicate(raw),
+                    lhs: operand(0),
+                    rhs: operand(1)
+                }
+            } else if !LLVMIsAFCmpInst(raw).is_null() {
+                InstructionKind::FCmp {
+                    predicate: LLVMGetFCmpPredicate(raw),
+                    lhs: operand(0),
+                    rhs: operand(1)
+                }
+            } else {
+                InstructionKind::Other(self.opcode())
+            }
+        }
+    }
+
+    /// The label of the block containing this instruction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instruction was unlinked with `InstructionBuilder::remove_from_parent`.
+    pub fn parent(&self) -> &Label<'fid> {
+        unsafe {
+            let block = LLVMGetInstructionParent(self.as_raw());
+            assert!(!block.is_null(), "instruction is not in a block");
+            &*(block as *mut Label)
+        }
+    }
+
+    pub fn downcast_value<'a, Ty: ?Sized>(value: &'a Value<'cid, 'mid, 'fid, Ty>) -> Result<&'a Instruction<'cid, 'mid, 'fid>, ()> {
+        unsafe {
+            let ret = LLVMIsAInstruction(value.as_raw());
+            if ret.is_null() {
+                Err(())
+            } else {
+                Ok(&*(ret as *mut Instruction))
+            }
+        }
+    }
+
+    pub fn as_value(&self) -> &Value<'cid, 'mid, 'fid, Type<'cid>> {
+        unsafe {
+            &*(self as *const _ as *const Value<Type>)
+        }
+    }
+
+    pub fn as_raw(&self) -> LLVMValueRef {
+        self.as_value().as_raw()
+    }
+}
+
+pub struct InstructionOperands<'a, 'cid: 'a, 'mid: 'a, 'fid: 'a> {
+    _instruction: PhantomData<&'a Instruction<'cid, 'mid, 'fid>>,
+    inner: LLVMValueRef,
+    index: usize,
+    len: usize
+}
+
+impl<'a, 'cid: 'a, 'mid: 'a, 'fid: 'a> Iterator for InstructionOperands<'a, 'cid, 'mid, 'fid> {
+    type Item = &'a Value<'cid, 'mid, 'fid, Type<'cid>>;
+
+    fn next(&mut self) -> Option<&'a Value<'cid, 'mid, 'fid, Type<'cid>>> {
+        if self.index == self.len {
+            None
+        } else {
+            unsafe {
+                let ret = Some(&*(LLVMGetOperand(self.inner, self.index as c_uint) as *mut Value<Type>));
+                self.index += 1;
+                ret
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len - self.index, Some(self.len - self.index))
+    }
+}
+
+impl<'a, 'cid: 'a, 'mid: 'a, 'fid: 'a> ExactSizeIterator for InstructionOperands<'a, 'cid, 'mid, 'fid> { }
+
+/// One use of an instruction as an operand of another instruction.
+pub struct Use<'cid, 'mid, 'fid> {
+    _context_id: IdRef<'cid>,
+    _module_id: IdRef<'mid>,
+    _function_id: IdRef<'fid>,
+    _opaque: Opaque
+}
+
+impl<'cid, 'mid, 'fid> Use<'cid, 'mid, 'fid> {
+    /// The instruction that has the used instruction as an operand.
+    pub fn user(&self) -> &Instruction<'cid, 'mid, 'fid> {
+        unsafe {
+            &*(LLVMGetUser(self.as_raw()) as *mut Instruction)
+        }
+    }
+
+    pub fn used_value(&self) -> &Instruction<'cid, 'mid, 'fid> {
+        unsafe {
+            &*(LLVMGetUsedValue(self.as_raw()) as *mut Instruction)
+        }
+    }
+
+    /// Which operand of the user this is.
+    pub fn operand_index(&self) -> usize {
+        unsafe {
+            (*(self.as_raw() as *mut ValueUse)).operand_index()
+        }
+    }
+
+    pub fn as_raw(&self) -> LLVMUseRef {
+        self as *const Use as *mut Use as LLVMUseRef
+    }
+}
+
+pub struct InstructionUses<'a, 'cid: 'a, 'mid: 'a, 'fid: 'a> {
+    _instruction: PhantomData<&'a Use<'cid, 'mid, 'fid>>,
+    inner: LLVMUseRef
+}
+
+impl<'a, 'cid: 'a, 'mid: 'a, 'fid: 'a> Iterator for InstructionUses<'a, 'cid, 'mid, 'fid> {
+    type Item = &'a Use<'cid, 'mid, 'fid>;
+
+    fn next(&mut self) -> Option<&'a Use<'cid, 'mid, 'fid>> {
+        if self.inner.is_null() {
+            None
+        } else {
+            unsafe {
+                let ret = Some(&*(self.inner as *const Use));
+                self.inner = LLVMGetNextUse(self.inner);
+                ret
+            }
+        }
+    }
+}
+
+/// A mutable view of an instruction, allowing it to be moved.
+///
+/// The moving methods take `&'block mut self`, consuming the handle, since the instruction may be
+/// in another block once they return.
+///
+/// There is no way to erase an instruction. Shared references to it can be reached without
+/// borrowing its block, for example through the use lists of its operands or of a constant, so no
+/// handle can prove that it is the only one left.
+pub struct InstructionBuilder<'cid: 'block, 'mid: 'block, 'fid: 'block, 'block, Ty: ?Sized + 'block = Type<'cid>> {
+    _block: PhantomData<&'block mut BasicBlock<'cid, 'mid, 'fid>>,
+    _type: PhantomData<Ty>,
+    _opaque: Opaque
+}
+
+impl<'cid, 'mid, 'fid, 'block, Ty: ?Sized> InstructionBuilder<'cid, 'mid, 'fid, 'block, Ty> {
+    pub fn instruction(&self) -> &Instruction<'cid, 'mid, 'fid> {
+        unsafe {
+            &*(self.as_raw() as *mut Instruction)
+        }
+    }
+
+    /// Gives the handle the type `ty`, if that is the LLVM type of the instruction's value.
+    /// Handles found by walking a block start out untyped.
+    pub fn downcast<'a, Specific: DerivesFrom<Type<'cid>> + ?Sized>(&'a mut self, ty: &Specific) -> Option<&'a mut InstructionBuilder<'cid, 'mid, 'fid, 'block, Specific>> {
+        unsafe {
+            if LLVMTypeOf(self.as_raw()) == upcast::<_, Type>(ty).as_raw() {
+                Some(&mut *(self.as_raw() as *mut InstructionBuilder<Specific>))
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Makes every user of the instruction use `replacement` instead, which must have the same
+    /// type as the instruction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `replacement` is the instruction itself, or has a different LLVM type. The
+    /// latter can only happen for untyped handles, since any other `Ty` pins down the LLVM type.
+    ///
+    /// ```compile_fail
+    /// # use std::ffi::CString;
+    /// # use llvm_safe::id;
+    /// # use llvm_safe::llvm::{Context, Module, Builder, Type, I32, I64};
+    /// id::with2(|context_id, module_id| {
+    ///     let context = Context::new(context_id);
+    ///     let name = CString::new("function").unwrap();
+    ///     let mut module = Module::new(module_id, &name, &context);
+    ///     let module_builder = module.builder();
+    ///     let mut builder = Builder::new(&context);
+    ///
+    ///     let function_ty = Type::typed_function::<(I32, I64), I32>(&context);
+    ///     let function = module_builder.add_function(&name, function_ty);
+    ///     id::with(|function_id| {
+    ///         let function_builder = function.builder(function_id);
+    ///         let (narrow, wide) = function_builder.typed_params();
+    ///         let (_, entry) = function_builder.append_basic_block(&name, &context);
+    ///         builder.position_at_end(entry).add(narrow, narrow, &name);
+    ///
+    ///         let sum = entry.instructions_mut().next().unwrap();
+    ///         sum.downcast(Type::int::<I32>(&context)).unwrap().replace_all_uses_with(wide);
+    ///     });
+    /// });
+    /// ```
+    pub fn replace_all_uses_with(&mut self, replacement: &Value<'cid, 'mid, 'fid, Ty>) {
+        unsafe {
+            assert!(LLVMTypeOf(self.as_raw()) == LLVMTypeOf(replacement.as_raw()), "replacement has a different type");
+            assert!(self.as_raw() != replacement.as_raw(), "cannot replace an instruction with itself");
+            LLVMReplaceAllUsesWith(self.as_raw(), replacement.as_raw());
+        }
+    }
+
+    /// Unlinks the instruction from its block, so that it can be put back somewhere else with
+    /// `PositionedBuilder::insert`.
+    pub fn remove_from_parent(&'block mut self) -> DetachedInstruction<'cid, 'mid, 'fid, 'block> {
+        unsafe {
+            let raw = self.as_raw();
+            let detached = DetachedInstruction {
+                _block: PhantomData,
+                block: LLVMGetInstructionParent(raw),
+                next: LLVMGetNextInstruction(raw),
+                inner: raw
+            };
+            LLVMInstructionRemoveFromParent(raw);
+            detached
+        }
+    }
+
+    /// Moves the instruction to just before `position`, which may be in a different block.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position` was unlinked with `remove_from_parent`.
+    pub fn move_before(&'block mut self, position: &Instruction<'cid, 'mid, 'fid>) {
+        unsafe {
+            reinsert(self.as_raw(), LLVMGetInstructionParent(position.as_raw()), position.as_raw());
+        }
+    }
+
+    /// Moves the instruction to just after `position`, which may be in a different block.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position` was unlinked with `remove_from_parent`.
+    pub fn move_after(&'block mut self, position: &Instruction<'cid, 'mid, 'fid>) {
+        unsafe {
+            reinsert(self.as_raw(), LLVMGetInstructionParent(position.as_raw()), LLVMGetNextInstruction(position.as_raw()));
+        }
+    }
+
+    pub fn as_raw(&self) -> LLVMValueRef {
+        self as *const InstructionBuilder<Ty> as *mut InstructionBuilder<Ty> as LLVMValueRef
+    }
+}
+
+/// An instruction unlinked from its block by `InstructionBuilder::remove_from_parent`.
+///
+/// Dropping it without inserting it somewhere puts it back where it was removed from, or at the
+/// end of that block if the instruction it was in front of has moved away since. It cannot be
+/// deleted instead, for the same reason `InstructionBuilder` cannot erase instructions.
+pub struct DetachedInstruction<'cid: 'block, 'mid: 'block, 'fid: 'block, 'block> {
+    _block: PhantomData<&'block mut BasicBlock<'cid, 'mid, 'fid>>,
+    block: LLVMBasicBlockRef,
+    next: LLVMValueRef,
+    inner: LLVMValueRef
+}
+
+impl<'cid, 'mid, 'fid, 'block> Drop for DetachedInstruction<'cid, 'mid, 'fid, 'block> {
+    fn drop(&mut self) {
+        unsafe {
+            let before = if !self.next.is_null() && LLVMGetInstructionParent(self.next) == self.block {
+                self.next
+            } else {
+                ptr::null_mut()
+            };
+            insert(self.inner, self.block, before);
+        }
+    }
+}
+
+impl<'cid, 'mid, 'fid, 'block> DetachedInstruction<'cid, 'mid, 'fid, 'block> {
+    pub fn instruction(&self) -> &Instruction<'cid, 'mid, 'fid> {
+        unsafe {
+            &*(self.inner as *mut Instruction)
+        }
+    }
+
+    pub fn as_raw(&self) -> LLVMValueRef {
+        self.inner
+    }
+}
+
+// The LLVM 8 C API has no way to move an instruction, so unlink it and insert it again with a
+// temporary builder, inserting at the end of `block` if `before` is null.
+unsafe fn reinsert(instruction: LLVMValueRef, block: LLVMBasicBlockRef, before: LLVMValueRef) {
+    assert!(!block.is_null(), "position is not in a block");
+    if instruction == before {
+        return;
+    }
+    LLVMInstructionRemoveFromParent(instruction);
+    insert(instruction, block, before);
+}
+
+unsafe fn insert(instruction: LLVMValueRef, block: LLVMBasicBlockRef, before: LLVMValueRef) {
+    // Inserting through a builder renames the instruction, so pass the old name along
+    let mut name_len = 0;
+    let name_ptr = LLVMGetValueName2(instruction, &mut name_len);
+    let name = if name_len == 0 {
+        CString::default()
+    } else {
+        CString::new(slice::from_raw_parts(name_ptr as *const u8, name_len)).unwrap_or_default()
+    };
+
+    let builder = LLVMCreateBuilderInContext(LLVMGetTypeContext(LLVMTypeOf(instruction)));
+    if before.is_null() {
+        LLVMPositionBuilderAtEnd(builder, block);
+    } else {
+        LLVMPositionBuilderBefore(builder, before);
+    }
+    LLVMInsertIntoBuilderWithName(builder, instruction, name.as_ptr());
+    LLVMDisposeBuilder(builder);
+}
@@ -0,0 +1,117 @@
+use std::ffi::CStr;
+use std::ptr;
+
+use libc::{c_char, c_uint, size_t};
+
+use llvm_sys::prelude::*;
+use llvm_sys::core::*;
+
+use ffi::MallocCStr;
+use inheritance::upcast;
+
+use llvm::{Context, ModuleBuilder, FunctionLabel, Type, FunctionType};
+
+/// An [intrinsic function][langref] known to LLVM, such as `llvm.sqrt` or `llvm.trap`.
+///
+/// [langref]: https://releases.llvm.org/8.0.1/docs/LangRef.html#intrinsic-functions
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Intrinsic {
+    id: c_uint
+}
+
+// Missing from llvm-sys 80.
+extern "C" {
+    fn LLVMLookupIntrinsicID(Name: *const c_char, NameLen: size_t) -> c_uint;
+}
+
+impl Intrinsic {
+    /// Looks up an intrinsic by its base name (e.g. `llvm.sadd.with.overflow`) or by a fully
+    /// mangled name (e.g. `llvm.sadd.with.overflow.i32`).
+    pub fn find(name: &CStr) -> Option<Intrinsic> {
+        unsafe {
+            let bytes = name.to_bytes();
+            let id = LLVMLookupIntrinsicID(bytes.as_ptr() as *const c_char, bytes.len() as size_t);
+            if id == 0 {
+                None
+            } else {
+                Some(Intrinsic { id })
+            }
+        }
+    }
+
+    pub fn of_function<'cid, 'mid>(function: &FunctionLabel<'cid, 'mid>) -> Option<Intrinsic> {
+        unsafe {
+            let id = LLVMGetIntrinsicID(function.as_raw());
+            if id == 0 {
+                None
+            } else {
+                Some(Intrinsic { id })
+            }
+        }
+    }
+
+    /// Whether the intrinsic is generic over some of its parameter or return types, like
+    /// `llvm.sqrt`, which must be given the floating point type to operate on.
+    pub fn is_overloaded(&self) -> bool {
+        unsafe {
+            LLVMIntrinsicIsOverloaded(self.id) != 0
+        }
+    }
+
+    /// Computes the type of the intrinsic, or returns `None` if it is overloaded.
+    ///
+    /// LLVM can instantiate an overloaded intrinsic's type, but it indexes the list of overloaded
+    /// types without checking its length or the kinds of types in it, and the C API has no way to
+    /// ask how many it needs. Overloaded intrinsics are instead declared with an explicit type by
+    /// `declare_overloaded`.
+    pub fn function_type<'ctx, 'cid>(&self, context: &'ctx Context<'cid>) -> Option<&'ctx FunctionType<'cid>> {
+        if self.is_overloaded() {
+            return None;
+        }
+        unsafe {
+            Some(&*(LLVMIntrinsicGetType(context.as_raw(), self.id, ptr::null_mut(), 0) as *mut FunctionType))
+        }
+    }
+
+    /// Declares the intrinsic in `module`, or finds an existing declaration. The returned label's
+    /// `function_type` is the intrinsic's type, so it can be passed straight to
+    /// `PositionedBuilder::call`. Returns `None` if the intrinsic is overloaded.
+    pub fn declare<'cid, 'mid, 'module>(&self, module: &mut ModuleBuilder<'cid, 'mid, 'module>) -> Option<&'module FunctionLabel<'cid, 'mid>> {
+        if self.is_overloaded() {
+            return None;
+        }
+        unsafe {
+            Some(&*(LLVMGetIntrinsicDeclaration(module.as_raw(), self.id, ptr::null_mut(), 0) as *mut FunctionLabel))
+        }
+    }
+
+    /// Declares an overloaded intrinsic, or finds an existing declaration. `overloaded` lists the
+    /// types the intrinsic is instantiated at, in the order the intrinsic's documentation gives
+    /// them, and only determines the declaration's name (e.g. `llvm.sqrt.f64`); `function_type`
+    /// is the instantiated type. A mismatch between the two is reported by the verifier.
+    ///
+    /// Returns `None` if the intrinsic is not overloaded, if `overloaded` is empty, or if the
+    /// module already has a function of the same name with a different type.
+    pub fn declare_overloaded<'cid, 'mid, 'module, Args: ?Sized, Ret: ?Sized>(&self, module: &mut ModuleBuilder<'cid, 'mid, 'module>, overloaded: &[&Type<'cid>], function_type: &FunctionType<'cid, Args, Ret>) -> Option<&'module FunctionLabel<'cid, 'mid, Args, Ret>> {
+        if !self.is_overloaded() || overloaded.is_empty() {
+            return None;
+        }
+        unsafe {
+            let mut name_len = 0;
+            let name = MallocCStr::from_raw(LLVMIntrinsicCopyOverloadedName(self.id, overloaded.as_ptr() as *mut LLVMTypeRef, overloaded.len() as size_t, &mut name_len));
+            let function_type = upcast::<_, Type>(function_type).as_raw();
+            let existing = LLVMGetNamedFunction(module.as_raw(), name.as_ptr());
+            if existing.is_null() {
+                Some(&*(LLVMAddFunction(module.as_raw(), name.as_ptr(), function_type) as *mut FunctionLabel<Args, Ret>))
+            } else if LLVMGlobalGetValueType(existing) == function_type {
+                Some(&*(existing as *mut FunctionLabel<Args, Ret>))
+            } else {
+                None
+            }
+        }
+    }
+
+    pub fn id(&self) -> c_uint {
+        self.id
+    }
+}
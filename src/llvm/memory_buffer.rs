@@ -0,0 +1,46 @@
+use std::ffi::CStr;
+use std::slice;
+
+use libc::size_t;
+
+use llvm_sys::prelude::LLVMMemoryBufferRef;
+use llvm_sys::core::{LLVMCreateMemoryBufferWithMemoryRangeCopy, LLVMDisposeMemoryBuffer};
+use llvm_sys::core::{LLVMGetBufferStart, LLVMGetBufferSize};
+
+use opaque::Opaque;
+use owned::{Owned, DropInPlace};
+
+/// A block of memory owned by LLVM, such as the output of code generation.
+pub struct MemoryBuffer {
+    _opaque: Opaque
+}
+
+impl DropInPlace for MemoryBuffer {
+    unsafe fn drop_in_place(&mut self) {
+        LLVMDisposeMemoryBuffer(self.as_raw());
+    }
+}
+
+impl MemoryBuffer {
+    /// Creates a buffer holding a copy of `data`. The name is used in diagnostics.
+    pub fn from_bytes(data: &[u8], name: &CStr) -> Owned<MemoryBuffer> {
+        unsafe {
+            Owned::from_raw(LLVMCreateMemoryBufferWithMemoryRangeCopy(data.as_ptr() as *const _, data.len() as size_t, name.as_ptr()) as *mut MemoryBuffer)
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe {
+            let len = LLVMGetBufferSize(self.as_raw());
+            if len == 0 {
+                &[]
+            } else {
+                slice::from_raw_parts(LLVMGetBufferStart(self.as_raw()) as *const u8, len as usize)
+            }
+        }
+    }
+
+    pub fn as_raw(&self) -> LLVMMemoryBufferRef {
+        self as *const MemoryBuffer as LLVMMemoryBufferRef
+    }
+}
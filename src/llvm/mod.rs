@@ -1,15 +1,29 @@
 pub use self::context::Context;
-pub use self::module::{Module, ModuleBuilder};
-pub use self::function::{Function, FunctionLabel, FunctionBuilder};
+pub use self::module::{Module, ModuleBuilder, ModuleGlobals};
+pub use self::function::{Function, FunctionLabel, FunctionBuilder, FunctionBlocks, BasicBlocks};
 pub use self::builder::{Builder, PositionedBuilder};
-pub use self::block::{BasicBlock, Label};
-pub use self::value::Value;
+pub use self::block::{BasicBlock, BlockInstructions, BlockInstructionsMut, Label};
+pub use self::instruction::{Instruction, InstructionBuilder, DetachedInstruction, InstructionOperands, InstructionUses, InstructionKind, Opcode, Use};
+pub use self::value::{Value, ValueUse, ValueUses};
 pub use self::phi::Phi;
 pub use self::alloca::Alloca;
-pub use self::constant::Constant;
-pub use self::global::Global;
-pub use self::ty::{Type, FunctionType, IntegerType, PointerType, ArrayType};
-pub use self::target::{Target, TargetMachine, DataLayout};
+pub use self::call::{Call, CallConv};
+pub use self::constant::{Constant, AnyModule, InModule, UsableIn, Join};
+pub use self::global::{Global, Linkage, Visibility, DLLStorageClass, UnnamedAddr, ThreadLocalMode};
+pub use self::ty::{Type, FunctionType, IntegerType, FloatType, PointerType, ArrayType, VectorType, StructType};
+pub use self::ty::{Int, IntWidth, IntOperand, I1, I8, I16, I32, I64, I128};
+pub use self::ty::{Float, FloatKind, FloatOperand, F16, F32, F64, X86FP80, FP128, PPCFP128};
+pub use self::alias::GlobalAlias;
+pub use self::comdat::{Comdat, ComdatSelectionKind};
+pub use self::intrinsic::Intrinsic;
+pub use self::inline_asm::{InlineAsm, AsmDialect};
+pub use self::signature::{Dynamic, Void, Ptr};
+pub use self::target::{Target, Targets, TargetMachine, TargetMachineBuilder, DataLayout, ByteOrder};
+pub use self::target::{OptLevel, RelocMode, CodeModel, CodeGenFileType};
+pub use self::memory_buffer::MemoryBuffer;
+pub use self::object::{ObjectFile, Sections, Section, Symbols, Symbol, Relocation};
+pub use self::disassembler::{Disassembler, Instructions};
+pub use self::init::TargetsInitialized;
 pub use self::pass_manager::{FunctionPassManager, InitializedFunctionPassManager};
 
 pub use llvm_sys::{LLVMIntPredicate, LLVMRealPredicate};
@@ -19,15 +33,25 @@ pub mod context;
 pub mod module;
 pub mod function;
 pub mod block;
+pub mod instruction;
 pub mod builder;
 pub mod value;
 pub mod phi;
 pub mod alloca;
+pub mod call;
 pub mod constant;
 pub mod ty;
 pub mod target;
 pub mod pass_manager;
 pub mod global;
+pub mod alias;
+pub mod comdat;
+pub mod intrinsic;
+pub mod inline_asm;
+pub mod signature;
+pub mod memory_buffer;
+pub mod object;
+pub mod disassembler;
 
 //
 // TODO: Error Checking
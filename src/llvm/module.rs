@@ -4,13 +4,14 @@ use std::marker::PhantomData;
 use llvm_sys::prelude::*;
 use llvm_sys::core::*;
 use llvm_sys::target::LLVMSetModuleDataLayout;
+use llvm_sys::comdat::LLVMGetOrInsertComdat;
 
 use id::{Id, IdRef};
 use inheritance::{upcast, DerivesFrom};
 use opaque::Opaque;
 use owned::{Owned, DropInPlace};
 
-use llvm::{Context, FunctionType, Type, Global, Function, FunctionLabel, DataLayout};
+use llvm::{Context, FunctionType, Type, PointerType, Constant, UsableIn, Global, GlobalAlias, Comdat, Function, FunctionLabel, DataLayout};
 
 pub struct Module<'cid: 'context, 'context, 'mid> {
     _id: Id<'mid>,
@@ -70,9 +71,57 @@ impl<'cid, 'mid, 'module> ModuleBuilder<'cid, 'mid, 'module> {
         }
     }
 
-    pub fn add_function(&mut self, name: &CStr, ty: &FunctionType<'cid>) -> &'module mut Function<'cid, 'mid> {
+    pub fn get_named_global(&self, name: &CStr) -> Option<&'module Global<'cid, 'mid, Type<'cid>>> {
         unsafe {
-            &mut *(LLVMAddFunction(self.as_raw(), name.as_ptr(), upcast::<_,Type>(ty).as_raw()) as *mut Function)
+            let global = LLVMGetNamedGlobal(self.as_raw(), name.as_ptr());
+            if global.is_null() {
+                None
+            } else {
+                Some(&*(global as *mut Global<Type>))
+            }
+        }
+    }
+
+    pub fn globals(&self) -> ModuleGlobals<'cid, 'mid, 'module> {
+        ModuleGlobals {
+            _context_id: IdRef::new(),
+            _module_id: IdRef::new(),
+            _module: PhantomData,
+            inner: unsafe { LLVMGetFirstGlobal(self.as_raw()) }
+        }
+    }
+
+    /// Removes a global variable from the module and destroys it. Any remaining uses of the global
+    /// are replaced with `undef`.
+    ///
+    /// # Safety
+    ///
+    /// The global must not be used after this call, including through other references returned
+    /// by `get_named_global` or `globals`. Replacing its uses rebuilds every constant expression
+    /// that mentions the global, so references to those constants must not be used either.
+    pub unsafe fn delete_global<Ty: ?Sized>(&mut self, global: &'module mut Global<'cid, 'mid, Ty>) {
+        let raw = global.as_raw();
+        LLVMReplaceAllUsesWith(raw, LLVMGetUndef(LLVMTypeOf(raw)));
+        LLVMDeleteGlobal(raw);
+    }
+
+    pub fn add_alias<Ty: ?Sized, Module: UsableIn<'mid>>(&mut self, name: &CStr, aliasee: &Constant<'cid, PointerType<'cid, Ty>, Module>) -> &'module mut GlobalAlias<'cid, 'mid, Ty> {
+        unsafe {
+            &mut *(LLVMAddAlias(self.as_raw(), LLVMTypeOf(aliasee.as_raw()), aliasee.as_raw(), name.as_ptr()) as *mut GlobalAlias<Ty>)
+        }
+    }
+
+    /// Looks up the COMDAT with the given name, creating it if there is none. Since repeated lookups
+    /// return the same COMDAT, the result borrows the builder.
+    pub fn get_or_insert_comdat<'a>(&'a mut self, name: &CStr) -> &'a mut Comdat<'mid> {
+        unsafe {
+            &mut *(LLVMGetOrInsertComdat(self.as_raw(), name.as_ptr()) as *mut Comdat)
+        }
+    }
+
+    pub fn add_function<Args: ?Sized, Ret: ?Sized>(&mut self, name: &CStr, ty: &FunctionType<'cid, Args, Ret>) -> &'module mut Function<'cid, 'mid, Args, Ret> {
+        unsafe {
+            &mut *(LLVMAddFunction(self.as_raw(), name.as_ptr(), upcast::<_,Type>(ty).as_raw()) as *mut Function<Args, Ret>)
         }
     }
 
@@ -87,6 +136,20 @@ impl<'cid, 'mid, 'module> ModuleBuilder<'cid, 'mid, 'module> {
         }
     }
 
+    /// Replaces the module level assembly, which is emitted verbatim at the start of the output.
+    pub fn set_inline_asm(&mut self, asm: &CStr) {
+        unsafe {
+            LLVMSetModuleInlineAsm2(self.as_raw(), asm.as_ptr(), asm.to_bytes().len());
+        }
+    }
+
+    /// Adds to the module level assembly, starting a new line if necessary.
+    pub fn append_inline_asm(&mut self, asm: &CStr) {
+        unsafe {
+            LLVMAppendModuleInlineAsm(self.as_raw(), asm.as_ptr(), asm.to_bytes().len());
+        }
+    }
+
     pub fn reborrow<'a>(&'a mut self) -> &'a mut ModuleBuilder<'cid, 'mid, 'a> {
         unsafe {
             &mut *(self.as_raw() as *mut ModuleBuilder)
@@ -97,3 +160,26 @@ impl<'cid, 'mid, 'module> ModuleBuilder<'cid, 'mid, 'module> {
         self as *const ModuleBuilder as *mut ModuleBuilder as LLVMModuleRef
     }
 }
+
+pub struct ModuleGlobals<'cid: 'module, 'mid: 'module, 'module> {
+    _context_id: IdRef<'cid>,
+    _module_id: IdRef<'mid>,
+    _module: PhantomData<&'module ModuleBuilder<'cid, 'mid, 'module>>,
+    inner: LLVMValueRef
+}
+
+impl<'cid: 'module, 'mid: 'module, 'module> Iterator for ModuleGlobals<'cid, 'mid, 'module> {
+    type Item = &'module Global<'cid, 'mid, Type<'cid>>;
+
+    fn next(&mut self) -> Option<&'module Global<'cid, 'mid, Type<'cid>>> {
+        if self.inner.is_null() {
+            None
+        } else {
+            unsafe {
+                let ret = Some(&*(self.inner as *const Global<Type>));
+                self.inner = LLVMGetNextGlobal(self.inner);
+                ret
+            }
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use std::ffi::CStr;
+use std::marker::PhantomData;
+
+use libc::{c_char, size_t};
+
+use llvm_sys::core::LLVMCreateMemoryBufferWithMemoryRange;
+use llvm_sys::object::{LLVMObjectFileRef, LLVMSectionIteratorRef, LLVMSymbolIteratorRef, LLVMRelocationIteratorRef};
+use llvm_sys::object::{LLVMCreateObjectFile, LLVMDisposeObjectFile};
+use llvm_sys::object::{LLVMGetSections, LLVMDisposeSectionIterator, LLVMIsSectionIteratorAtEnd, LLVMMoveToNextSection};
+use llvm_sys::object::{LLVMGetSectionName, LLVMGetSectionAddress, LLVMGetSectionSize, LLVMGetSectionContents};
+use llvm_sys::object::{LLVMGetSymbols, LLVMDisposeSymbolIterator, LLVMIsSymbolIteratorAtEnd, LLVMMoveToNextSymbol};
+use llvm_sys::object::{LLVMGetSymbolName, LLVMGetSymbolAddress, LLVMGetSymbolSize};
+use llvm_sys::object::{LLVMGetRelocations, LLVMDisposeRelocationIterator, LLVMIsRelocationIteratorAtEnd, LLVMMoveToNextRelocation};
+use llvm_sys::object::{LLVMGetRelocationOffset, LLVMGetRelocationType, LLVMGetRelocationSymbol};
+
+use opaque::Opaque;
+use owned::{Owned, DropInPlace};
+
+use llvm::MemoryBuffer;
+
+/// A parsed object file, such as one produced by `TargetMachine::emit_module_to_memory`. The
+/// object file reads straight out of the buffer it was parsed from, which it borrows.
+pub struct ObjectFile<'buf> {
+    _buffer: PhantomData<&'buf MemoryBuffer>,
+    _opaque: Opaque
+}
+
+impl<'buf> DropInPlace for ObjectFile<'buf> {
+    unsafe fn drop_in_place(&mut self) {
+        LLVMDisposeObjectFile(self.as_raw());
+    }
+}
+
+impl<'buf> ObjectFile<'buf> {
+    /// Parses the object file held in `buffer`. Returns `None` if the buffer does not contain an
+    /// object file in a format LLVM understands.
+    pub fn new(buffer: &'buf MemoryBuffer) -> Option<Owned<ObjectFile<'buf>>> {
+        unsafe {
+            let bytes = buffer.as_bytes();
+            // LLVM takes ownership of the buffer it is given, even if parsing fails, so hand it
+            // one that only refers to the caller's bytes.
+            let view = LLVMCreateMemoryBufferWithMemoryRange(bytes.as_ptr() as *const c_char, bytes.len() as size_t, b"\0".as_ptr() as *const c_char, 0);
+            let object = LLVMCreateObjectFile(view);
+            if object.is_null() {
+                None
+            } else {
+                Some(Owned::from_raw(object as *mut ObjectFile))
+            }
+        }
+    }
+
+    pub fn sections<'obj>(&'obj self) -> Sections<'buf, 'obj> {
+        Sections {
+            object: self,
+            inner: unsafe { LLVMGetSections(self.as_raw()) }
+        }
+    }
+
+    pub fn symbols<'obj>(&'obj self) -> Symbols<'buf, 'obj> {
+        Symbols {
+            object: self,
+            inner: unsafe { LLVMGetSymbols(self.as_raw()) }
+        }
+    }
+
+    pub fn as_raw(&self) -> LLVMObjectFileRef {
+        self as *const ObjectFile as *mut ObjectFile as LLVMObjectFileRef
+    }
+}
+
+pub struct Sections<'buf: 'obj, 'obj> {
+    object: &'obj ObjectFile<'buf>,
+    inner: LLVMSectionIteratorRef
+}
+
+impl<'buf, 'obj> Drop for Sections<'buf, 'obj> {
+    fn drop(&mut self) {
+        unsafe {
+            LLVMDisposeSectionIterator(self.inner);
+        }
+    }
+}
+
+impl<'buf, 'obj> Iterator for Sections<'buf, 'obj> {
+    type Item = Section<'obj>;
+
+    fn next(&mut self) -> Option<Section<'obj>> {
+        unsafe {
+            if LLVMIsSectionIteratorAtEnd(self.object.as_raw(), self.inner) != 0 {
+                None
+            } else {
+                // The C API can only list relocations through a section iterator, and those
+                // cannot be copied, so read them while this one is still on the section.
+                let mut relocations = Vec::new();
+                let inner = LLVMGetRelocations(self.inner);
+                while LLVMIsRelocationIteratorAtEnd(self.inner, inner) == 0 {
+                    relocations.push(Relocation::from_raw(self.object, inner));
+                    LLVMMoveToNextRelocation(inner);
+                }
+                LLVMDisposeRelocationIterator(inner);
+
+                let ret = Section {
+                    name: name_from_raw(LLVMGetSectionName(self.inner)),
+                    address: LLVMGetSectionAddress(self.inner),
+                    size: LLVMGetSectionSize(self.inner),
+                    contents: LLVMGetSectionContents(self.inner) as *const u8,
+                    relocations
+                };
+                LLVMMoveToNextSection(self.inner);
+                Some(ret)
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Section<'obj> {
+    name: &'obj CStr,
+    address: u64,
+    size: u64,
+    contents: *const u8,
+    relocations: Vec<Relocation<'obj>>
+}
+
+impl<'obj> Section<'obj> {
+    pub fn name(&self) -> &'obj CStr {
+        self.name
+    }
+
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// The bytes stored for this section in `buffer`, which should be the one the object file was
+    /// parsed from. This is empty for sections like `.bss` that take up no space in the file.
+    ///
+    /// The C API only says where a section's bytes start, and sections that take up no space in
+    /// the file still report the size they take up when loaded, so the result is cut down to what
+    /// actually lies inside `buffer`. That keeps the slice valid even when given the wrong buffer.
+    /// LLVM points such sections at the very start of the file, which is always a header, so no
+    /// real section starts there.
+    pub fn contents<'a>(&self, buffer: &'a MemoryBuffer) -> &'a [u8] {
+        let bytes = buffer.as_bytes();
+        let start = self.contents as usize;
+        let buffer_start = bytes.as_ptr() as usize;
+        if start <= buffer_start || start > buffer_start + bytes.len() || self.size > (buffer_start + bytes.len() - start) as u64 {
+            &[]
+        } else {
+            &bytes[start - buffer_start..start - buffer_start + self.size as usize]
+        }
+    }
+
+    /// The relocations this section applies, usually to some other section.
+    pub fn relocations(&self) -> &[Relocation<'obj>] {
+        &self.relocations
+    }
+}
+
+pub struct Symbols<'buf: 'obj, 'obj> {
+    object: &'obj ObjectFile<'buf>,
+    inner: LLVMSymbolIteratorRef
+}
+
+impl<'buf, 'obj> Drop for Symbols<'buf, 'obj> {
+    fn drop(&mut self) {
+        unsafe {
+            LLVMDisposeSymbolIterator(self.inner);
+        }
+    }
+}
+
+impl<'buf, 'obj> Iterator for Symbols<'buf, 'obj> {
+    type Item = Symbol<'obj>;
+
+    fn next(&mut self) -> Option<Symbol<'obj>> {
+        unsafe {
+            if LLVMIsSymbolIteratorAtEnd(self.object.as_raw(), self.inner) != 0 {
+                None
+            } else {
+                let ret = Symbol::from_raw(self.inner);
+                LLVMMoveToNextSymbol(self.inner);
+                Some(ret)
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Symbol<'obj> {
+    name: &'obj CStr,
+    address: u64,
+    size: u64
+}
+
+impl<'obj> Symbol<'obj> {
+    unsafe fn from_raw(raw: LLVMSymbolIteratorRef) -> Symbol<'obj> {
+        Symbol {
+            name: name_from_raw(LLVMGetSymbolName(raw)),
+            address: LLVMGetSymbolAddress(raw),
+            size: LLVMGetSymbolSize(raw)
+        }
+    }
+
+    pub fn name(&self) -> &'obj CStr {
+        self.name
+    }
+
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Relocation<'obj> {
+    offset: u64,
+    kind: u64,
+    symbol: Option<Symbol<'obj>>
+}
+
+impl<'obj> Relocation<'obj> {
+    unsafe fn from_raw(object: &ObjectFile, raw: LLVMRelocationIteratorRef) -> Relocation<'obj> {
+        let symbol = LLVMGetRelocationSymbol(raw);
+        let ret = Relocation {
+            offset: LLVMGetRelocationOffset(raw),
+            kind: LLVMGetRelocationType(raw),
+            symbol: if LLVMIsSymbolIteratorAtEnd(object.as_raw(), symbol) != 0 {
+                None
+            } else {
+                Some(Symbol::from_raw(symbol))
+            }
+        };
+        LLVMDisposeSymbolIterator(symbol);
+        ret
+    }
+
+    /// The offset of the patched location, relative to the start of its section.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// The format specific relocation type, e.g. `R_X86_64_PLT32` for ELF on x86-64.
+    pub fn kind(&self) -> u64 {
+        self.kind
+    }
+
+    /// The symbol whose address is patched in, if any.
+    pub fn symbol(&self) -> Option<Symbol<'obj>> {
+        self.symbol
+    }
+}
+
+// Unnamed sections and symbols, like the null section at the start of an ELF file, may come back
+// as a null pointer instead of an empty string.
+unsafe fn name_from_raw<'obj>(raw: *const c_char) -> &'obj CStr {
+    if raw.is_null() {
+        CStr::from_bytes_with_nul(b"\0").unwrap()
+    } else {
+        CStr::from_ptr(raw)
+    }
+}
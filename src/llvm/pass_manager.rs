@@ -117,7 +117,7 @@ impl<'mid, 'module, 'fpm> DropInPlace for InitializedFunctionPassManager<'mid, '
 }
 
 impl<'mid, 'module, 'fpm> InitializedFunctionPassManager<'mid, 'module, 'fpm> {
-    pub fn run<'cid>(&mut self, function: &mut Function<'cid, 'mid>) {
+    pub fn run<'cid, Args: ?Sized, Ret: ?Sized>(&mut self, function: &mut Function<'cid, 'mid, Args, Ret>) {
         unsafe {
             LLVMRunFunctionPassManager(self.as_raw(), function.as_raw());
         }
@@ -0,0 +1,333 @@
+use std::marker::PhantomData;
+
+use llvm_sys::prelude::*;
+use llvm_sys::core::*;
+
+use libc::c_uint;
+
+use inheritance::{upcast, DerivesFrom};
+
+use llvm::{Context, Value, Type, FunctionType, Int, Float, PointerType, ArrayType, I1, I8, I16, I32, I64, I128};
+use llvm::{F16, F32, F64, X86FP80, FP128, PPCFP128};
+
+/// Types, parameter lists or return types that are only known at runtime.
+pub enum Dynamic { }
+
+/// The `void` return type. This is only a `ReturnMarker`, since LLVM has no `void` parameters,
+/// pointers or fields.
+///
+/// ```compile_fail
+/// # use llvm_safe::id;
+/// # use llvm_safe::llvm::{Context, Type, Void};
+/// id::with(|context_id| {
+///     let context = Context::new(context_id);
+///     Type::typed_function::<(Void,), Void>(&context);
+/// });
+/// ```
+pub enum Void { }
+
+/// A pointer, in the default address space, to a value described by the marker `Pointee`.
+///
+/// ```compile_fail
+/// # use llvm_safe::id;
+/// # use llvm_safe::llvm::{Context, Ptr, Void};
+/// # use llvm_safe::llvm::signature::StaticTypeMarker;
+/// id::with(|context_id| {
+///     let context = Context::new(context_id);
+///     Ptr::<Void>::get(&context);
+/// });
+/// ```
+pub struct Ptr<Pointee: ?Sized> {
+    _pointee: PhantomData<Pointee>
+}
+
+/// A marker standing for an LLVM type.
+///
+/// # Safety
+///
+/// Every LLVM type the marker stands for must be represented by `Self::Type`.
+pub unsafe trait TypeMarker<'cid> {
+    /// The type of values of the marked type.
+    type Type: DerivesFrom<Type<'cid>> + ?Sized;
+}
+
+/// A marker whose type is fully determined statically, so it can be created from just a context.
+///
+/// # Safety
+///
+/// `get` must always return the same type for a given context.
+pub unsafe trait StaticTypeMarker<'cid>: TypeMarker<'cid> {
+    fn get<'ctx>(context: &'ctx Context<'cid>) -> &'ctx Self::Type;
+}
+
+unsafe impl<'cid> TypeMarker<'cid> for Dynamic {
+    type Type = Type<'cid>;
+}
+
+/// A marker standing for a function's return type. Every `TypeMarker` is one, as is `Void`.
+///
+/// # Safety
+///
+/// Calls to a function with the marked return type must produce values of type `Self::Type`.
+pub unsafe trait ReturnMarker<'cid> {
+    /// The type of the result of calling a function with the marked return type.
+    type Type: DerivesFrom<Type<'cid>> + ?Sized;
+}
+
+/// A return marker whose type is fully determined statically.
+///
+/// # Safety
+///
+/// `get` must always return the same type for a given context.
+pub unsafe trait StaticReturnMarker<'cid>: ReturnMarker<'cid> {
+    fn get<'ctx>(context: &'ctx Context<'cid>) -> &'ctx Self::Type;
+}
+
+unsafe impl<'cid, T: TypeMarker<'cid> + ?Sized> ReturnMarker<'cid> for T {
+    type Type = T::Type;
+}
+
+unsafe impl<'cid, T: StaticTypeMarker<'cid> + ?Sized> StaticReturnMarker<'cid> for T {
+    fn get<'ctx>(context: &'ctx Context<'cid>) -> &'ctx T::Type {
+        T::get(context)
+    }
+}
+
+unsafe impl<'cid> ReturnMarker<'cid> for Void {
+    type Type = Type<'cid>;
+}
+
+unsafe impl<'cid> StaticReturnMarker<'cid> for Void {
+    fn get<'ctx>(context: &'ctx Context<'cid>) -> &'ctx Type<'cid> {
+        Type::void(context)
+    }
+}
+
+unsafe impl<'cid, Pointee: TypeMarker<'cid> + ?Sized> TypeMarker<'cid> for Ptr<Pointee> {
+    type Type = PointerType<'cid, Pointee::Type>;
+}
+
+unsafe impl<'cid, Pointee: StaticTypeMarker<'cid> + ?Sized> StaticTypeMarker<'cid> for Ptr<Pointee> {
+    fn get<'ctx>(context: &'ctx Context<'cid>) -> &'ctx PointerType<'cid, Pointee::Type> {
+        Type::pointer(Pointee::get(context), 0)
+    }
+}
+
+macro_rules! int_marker_impl {
+    ( $($width:ident)* ) => { $(
+        unsafe impl<'cid> TypeMarker<'cid> for $width {
+            type Type = Int<'cid, $width>;
+        }
+
+        unsafe impl<'cid> StaticTypeMarker<'cid> for $width {
+            fn get<'ctx>(context: &'ctx Context<'cid>) -> &'ctx Int<'cid, $width> {
+                Type::int::<$width>(context)
+            }
+        }
+    )* };
+}
+
+int_marker_impl!{ I1 I8 I16 I32 I64 I128 }
+
+macro_rules! float_marker_impl {
+    ( $($kind:ident)* ) => { $(
+        unsafe impl<'cid> TypeMarker<'cid> for $kind {
+            type Type = Float<'cid, $kind>;
+        }
+
+        unsafe impl<'cid> StaticTypeMarker<'cid> for $kind {
+            fn get<'ctx>(context: &'ctx Context<'cid>) -> &'ctx Float<'cid, $kind> {
+                Type::float::<$kind>(context)
+            }
+        }
+    )* };
+}
+
+float_marker_impl!{ F16 F32 F64 X86FP80 FP128 PPCFP128 }
+
+/// A Rust type whose in-memory layout matches that of the LLVM type it stands for.
+///
+/// Markers like `I32`, `Ptr<T>` and `F64` only name LLVM types and have no meaningful Rust layout,
+/// so they do not implement this trait. `#[derive(LlvmType)]` from `llvm-safe-derive` requires it
+/// of every field.
+///
+/// # Safety
+///
+/// The size, alignment and field offsets of `Self` must agree with those of `Self::Type` under the
+/// data layout of the target the code is compiled for.
+pub unsafe trait RustLayout<'cid>: StaticTypeMarker<'cid> { }
+
+// Rust types can stand for the LLVM types with the same layout, which lets `#[derive(LlvmType)]`
+// describe `#[repr(C)]` structs field by field. `i128` and `u128` are left out because rustc and
+// LLVM 8 data layouts disagree on their alignment on some targets.
+
+macro_rules! rust_marker_impl {
+    ( $($rust_ty:ty, $marker:ident)* ) => { $(
+        unsafe impl<'cid> TypeMarker<'cid> for $rust_ty {
+            type Type = <$marker as TypeMarker<'cid>>::Type;
+        }
+
+        unsafe impl<'cid> StaticTypeMarker<'cid> for $rust_ty {
+            fn get<'ctx>(context: &'ctx Context<'cid>) -> &'ctx Self::Type {
+                <$marker as StaticTypeMarker<'cid>>::get(context)
+            }
+        }
+
+        unsafe impl<'cid> RustLayout<'cid> for $rust_ty { }
+    )* };
+}
+
+rust_marker_impl!{
+    bool, I1
+    i8, I8
+    u8, I8
+    i16, I16
+    u16, I16
+    i32, I32
+    u32, I32
+    i64, I64
+    u64, I64
+    f32, F32
+    f64, F64
+}
+
+#[cfg(target_pointer_width = "16")]
+rust_marker_impl!{ isize, I16 usize, I16 }
+#[cfg(target_pointer_width = "32")]
+rust_marker_impl!{ isize, I32 usize, I32 }
+#[cfg(target_pointer_width = "64")]
+rust_marker_impl!{ isize, I64 usize, I64 }
+
+// Thin pointers are all treated as `i8*`, since following the pointee would recurse forever on
+// self-referential structs.
+macro_rules! rust_pointer_marker_impl {
+    ( $($rust_ty:ty)* ) => { $(
+        unsafe impl<'cid, 'a, T> TypeMarker<'cid> for $rust_ty {
+            type Type = PointerType<'cid, Int<'cid, I8>>;
+        }
+
+        unsafe impl<'cid, 'a, T> StaticTypeMarker<'cid> for $rust_ty {
+            fn get<'ctx>(context: &'ctx Context<'cid>) -> &'ctx Self::Type {
+                <Ptr<I8> as StaticTypeMarker<'cid>>::get(context)
+            }
+        }
+
+        unsafe impl<'cid, 'a, T> RustLayout<'cid> for $rust_ty { }
+    )* };
+}
+
+rust_pointer_marker_impl!{ *const T *mut T &'a T &'a mut T }
+
+macro_rules! array_marker_impl {
+    ( $($len:expr)* ) => { $(
+        unsafe impl<'cid, T: TypeMarker<'cid>> TypeMarker<'cid> for [T; $len] {
+            type Type = ArrayType<'cid, T::Type>;
+        }
+
+        unsafe impl<'cid, T: StaticTypeMarker<'cid>> StaticTypeMarker<'cid> for [T; $len] {
+            fn get<'ctx>(context: &'ctx Context<'cid>) -> &'ctx ArrayType<'cid, T::Type> {
+                Type::array(T::get(context), $len)
+            }
+        }
+
+        unsafe impl<'cid, T: RustLayout<'cid>> RustLayout<'cid> for [T; $len] { }
+    )* };
+}
+
+// Lengths up to 32, like the standard library's array impls before const generics.
+array_marker_impl!{ 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32 }
+
+/// A statically known list of parameter types, written as a tuple of markers.
+///
+/// # Safety
+///
+/// `raw_types` must return valid types in `context`, one for each parameter.
+pub unsafe trait ArgTypes<'cid> {
+    fn raw_types(context: &Context<'cid>) -> Vec<LLVMTypeRef>;
+}
+
+/// The argument values that can be passed to a function whose parameters are described by `Self`.
+///
+/// # Safety
+///
+/// `raw_values` must return one value for each parameter, each of the parameter's type.
+pub unsafe trait ArgValues<'a, 'cid, 'mid, 'fid> {
+    type Values;
+
+    fn raw_values(values: &Self::Values) -> Vec<LLVMValueRef>;
+}
+
+/// Parameter lists whose values can be read back out of a function definition.
+///
+/// # Safety
+///
+/// `from_function` must only wrap parameters in the value types given by the markers.
+pub unsafe trait ParamValues<'a, 'cid, 'mid, 'fid>: ArgValues<'a, 'cid, 'mid, 'fid> {
+    /// Reads the parameters of `function`.
+    ///
+    /// # Safety
+    ///
+    /// `function` must be a valid function whose parameters are described by `Self`.
+    unsafe fn from_function(function: LLVMValueRef) -> Self::Values;
+}
+
+unsafe impl<'a, 'cid: 'a, 'mid: 'a, 'fid: 'a> ArgValues<'a, 'cid, 'mid, 'fid> for Dynamic {
+    type Values = &'a [&'a Value<'cid, 'mid, 'fid, Type<'cid>>];
+
+    fn raw_values(values: &Self::Values) -> Vec<LLVMValueRef> {
+        values.iter().map(|value| value.as_raw()).collect()
+    }
+}
+
+macro_rules! arg_tuple_impl {
+    ( $( ($($arg:ident $index:expr),*) )* ) => { $(
+        unsafe impl<'cid, $($arg: StaticTypeMarker<'cid>),*> ArgTypes<'cid> for ($($arg,)*) {
+            #[allow(unused_variables)]
+            fn raw_types(context: &Context<'cid>) -> Vec<LLVMTypeRef> {
+                vec![$(upcast::<_,Type>($arg::get(context)).as_raw()),*]
+            }
+        }
+
+        unsafe impl<'a, 'cid: 'a, 'mid: 'a, 'fid: 'a, $($arg: TypeMarker<'cid>),*> ArgValues<'a, 'cid, 'mid, 'fid> for ($($arg,)*) where $($arg::Type: 'a),* {
+            type Values = ($(&'a Value<'cid, 'mid, 'fid, $arg::Type>,)*);
+
+            #[allow(non_snake_case, unused_variables)]
+            fn raw_values(values: &Self::Values) -> Vec<LLVMValueRef> {
+                let &($($arg,)*) = values;
+                vec![$($arg.as_raw()),*]
+            }
+        }
+
+        unsafe impl<'a, 'cid: 'a, 'mid: 'a, 'fid: 'a, $($arg: TypeMarker<'cid>),*> ParamValues<'a, 'cid, 'mid, 'fid> for ($($arg,)*) where $($arg::Type: 'a),* {
+            #[allow(unused_variables, clippy::unused_unit)]
+            unsafe fn from_function(function: LLVMValueRef) -> Self::Values {
+                ($(&*(LLVMGetParam(function, $index as c_uint) as *const Value<$arg::Type>),)*)
+            }
+        }
+    )* };
+}
+
+arg_tuple_impl!{
+    ()
+    (A 0)
+    (A 0, B 1)
+    (A 0, B 1, C 2)
+    (A 0, B 1, C 2, D 3)
+    (A 0, B 1, C 2, D 3, E 4)
+    (A 0, B 1, C 2, D 3, E 4, F 5)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7)
+}
+
+/// The type of values that can be called as a function of type `FunctionType<'cid, Args, Ret>`.
+/// Besides pointers to that function type, an untyped `Type` value can be called through an
+/// untyped function type.
+///
+/// # Safety
+///
+/// Values of type `Self` must be callable with arguments described by `Args`, producing a result
+/// described by `Ret`.
+pub unsafe trait Callee<'cid, Args: ?Sized, Ret: ?Sized>: DerivesFrom<Type<'cid>> { }
+
+unsafe impl<'cid> Callee<'cid, Dynamic, Dynamic> for Type<'cid> { }
+unsafe impl<'cid, Args: ?Sized, Ret: ?Sized> Callee<'cid, Args, Ret> for PointerType<'cid, FunctionType<'cid, Args, Ret>> { }
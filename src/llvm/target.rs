@@ -1,21 +1,39 @@
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
 use std::mem;
 
-pub use llvm_sys::target_machine::{LLVMCodeGenOptLevel, LLVMRelocMode, LLVMCodeModel};
-use llvm_sys::target_machine::{LLVMTargetRef, LLVMGetTargetFromTriple, LLVMGetDefaultTargetTriple};
+use llvm_sys::target_machine::{LLVMCodeGenOptLevel, LLVMRelocMode, LLVMCodeModel};
+use llvm_sys::target_machine::{LLVMTargetRef, LLVMGetTargetFromTriple, LLVMGetDefaultTargetTriple, LLVMNormalizeTargetTriple};
+use llvm_sys::target_machine::{LLVMGetHostCPUName, LLVMGetHostCPUFeatures};
+use llvm_sys::target_machine::{LLVMGetFirstTarget, LLVMGetNextTarget, LLVMGetTargetFromName};
+use llvm_sys::target_machine::{LLVMGetTargetName, LLVMGetTargetDescription};
+use llvm_sys::target_machine::{LLVMTargetHasJIT, LLVMTargetHasTargetMachine, LLVMTargetHasAsmBackend};
 use llvm_sys::target_machine::{LLVMTargetMachineRef, LLVMCreateTargetMachine, LLVMDisposeTargetMachine};
+use llvm_sys::target_machine::{LLVMGetTargetMachineTarget, LLVMGetTargetMachineTriple, LLVMGetTargetMachineCPU, LLVMGetTargetMachineFeatureString};
+use llvm_sys::target_machine::LLVMAddAnalysisPasses;
 
 use llvm_sys::target::{LLVMTargetDataRef, LLVMCopyStringRepOfTargetData, LLVMDisposeTargetData, LLVMCreateTargetData};
+use llvm_sys::target::{LLVMStoreSizeOfType, LLVMABISizeOfType, LLVMABIAlignmentOfType, LLVMPreferredAlignmentOfType};
+use llvm_sys::target::{LLVMPointerSizeForAS, LLVMIntPtrTypeInContext, LLVMByteOrder, LLVMByteOrdering};
+use llvm_sys::target::{LLVMOffsetOfElement, LLVMElementAtOffset};
+use llvm_sys::core::LLVMTypeIsSized;
+use llvm_sys::prelude::LLVMTypeRef;
 use llvm_sys::target_machine::LLVMCreateTargetDataLayout;
 
-pub use llvm_sys::target_machine::LLVMCodeGenFileType;
-use llvm_sys::target_machine::LLVMTargetMachineEmitToFile;
+use llvm_sys::target_machine::LLVMCodeGenFileType;
+use llvm_sys::target_machine::{LLVMTargetMachineEmitToFile, LLVMTargetMachineEmitToMemoryBuffer};
+
+use libc::{c_uint, c_ulonglong};
 
 use ffi::MallocCStr;
+use inheritance::{upcast, DerivesFrom};
 use opaque::Opaque;
 use owned::{Owned, DropInPlace};
 
 use llvm::module::Module;
+use llvm::init::TargetsInitialized;
+use llvm::memory_buffer::MemoryBuffer;
+use llvm::pass_manager::FunctionPassManager;
+use llvm::{Context, Type, IntegerType, StructType};
 
 pub fn default_triple() -> Owned<MallocCStr> {
     unsafe {
@@ -23,12 +41,33 @@ pub fn default_triple() -> Owned<MallocCStr> {
     }
 }
 
+/// Converts a target triple to LLVM's canonical form, e.g. filling in missing components.
+pub fn normalize_triple(triple: &CStr) -> Owned<MallocCStr> {
+    unsafe {
+        MallocCStr::from_raw(LLVMNormalizeTargetTriple(triple.as_ptr()))
+    }
+}
+
+/// The name of the host's CPU, suitable for passing to `TargetMachineBuilder::cpu`.
+pub fn host_cpu_name() -> Owned<MallocCStr> {
+    unsafe {
+        MallocCStr::from_raw(LLVMGetHostCPUName())
+    }
+}
+
+/// The features supported by the host's CPU, as a comma separated list like `+sse2,-avx`.
+pub fn host_cpu_features() -> Owned<MallocCStr> {
+    unsafe {
+        MallocCStr::from_raw(LLVMGetHostCPUFeatures())
+    }
+}
+
 pub struct Target {
     _opaque: Opaque
 }
 
 impl Target {
-    pub fn from_triple(triple: &CStr) -> Result<&'static Target, Owned<MallocCStr>> {
+    pub fn from_triple(_init: TargetsInitialized, triple: &CStr) -> Result<&'static Target, Owned<MallocCStr>> {
         unsafe {
             let mut target_ptr = mem::MaybeUninit::uninit();
             let mut err_ptr = mem::MaybeUninit::uninit();
@@ -40,11 +79,80 @@ impl Target {
         }
     }
 
+    /// Looks up a target by its short name, like `x86-64` or `aarch64`.
+    pub fn from_name(_init: TargetsInitialized, name: &CStr) -> Option<&'static Target> {
+        unsafe {
+            let target = LLVMGetTargetFromName(name.as_ptr());
+            if target.is_null() {
+                None
+            } else {
+                Some(&*(target as *mut Target))
+            }
+        }
+    }
+
+    /// Iterates over every registered target.
+    pub fn all(_init: TargetsInitialized) -> Targets {
+        Targets {
+            inner: unsafe { LLVMGetFirstTarget() }
+        }
+    }
+
+    pub fn name(&self) -> &'static CStr {
+        unsafe {
+            CStr::from_ptr(LLVMGetTargetName(self.as_raw()))
+        }
+    }
+
+    pub fn description(&self) -> &'static CStr {
+        unsafe {
+            CStr::from_ptr(LLVMGetTargetDescription(self.as_raw()))
+        }
+    }
+
+    pub fn has_jit(&self) -> bool {
+        unsafe {
+            LLVMTargetHasJIT(self.as_raw()) != 0
+        }
+    }
+
+    pub fn has_target_machine(&self) -> bool {
+        unsafe {
+            LLVMTargetHasTargetMachine(self.as_raw()) != 0
+        }
+    }
+
+    pub fn has_asm_backend(&self) -> bool {
+        unsafe {
+            LLVMTargetHasAsmBackend(self.as_raw()) != 0
+        }
+    }
+
     pub fn as_raw(&self) -> LLVMTargetRef {
         self as *const Target as LLVMTargetRef
     }
 }
 
+pub struct Targets {
+    inner: LLVMTargetRef
+}
+
+impl Iterator for Targets {
+    type Item = &'static Target;
+
+    fn next(&mut self) -> Option<&'static Target> {
+        if self.inner.is_null() {
+            None
+        } else {
+            unsafe {
+                let ret = Some(&*(self.inner as *const Target));
+                self.inner = LLVMGetNextTarget(self.inner);
+                ret
+            }
+        }
+    }
+}
+
 pub struct TargetMachine {
     _opaque: Opaque
 }
@@ -56,16 +164,69 @@ impl DropInPlace for TargetMachine {
 }
 
 impl TargetMachine {
-    pub fn new(target: &Target, triple: &CStr, cpu: &CStr, features: &CStr, opt_level: LLVMCodeGenOptLevel, reloc_mode: LLVMRelocMode, code_model: LLVMCodeModel) -> Owned<TargetMachine> {
+    /// Starts building a target machine. Everything not explicitly set is left at LLVM's defaults,
+    /// for the default target triple.
+    pub fn builder<'a>(init: TargetsInitialized) -> TargetMachineBuilder<'a> {
+        TargetMachineBuilder {
+            init,
+            triple: None,
+            cpu: "",
+            features: "",
+            opt_level: OptLevel::Default,
+            reloc_mode: RelocMode::Default,
+            code_model: CodeModel::Default
+        }
+    }
+
+    /// Creates a target machine generating code for the host, using its CPU and all of its
+    /// features.
+    pub fn native(init: TargetsInitialized, opt_level: OptLevel) -> Result<Owned<TargetMachine>, Owned<MallocCStr>> {
+        // LLVM's CPU names and feature strings are plain ASCII.
+        let cpu = host_cpu_name();
+        let features = host_cpu_features();
+        TargetMachine::builder(init)
+            .cpu(cpu.to_str().expect("host CPU name is not UTF-8"))
+            .features(features.to_str().expect("host CPU features are not UTF-8"))
+            .opt_level(opt_level)
+            .build()
+    }
+
+    pub fn target(&self) -> &'static Target {
         unsafe {
-            Owned::from_raw(LLVMCreateTargetMachine(target.as_raw(), triple.as_ptr(), cpu.as_ptr(), features.as_ptr(), opt_level, reloc_mode, code_model) as *mut TargetMachine)
+            &*(LLVMGetTargetMachineTarget(self.as_raw()) as *mut Target)
         }
     }
 
-    pub fn emit_module_to_file<'cid, 'mid, 'context>(&self, module: &Module<'cid, 'mid, 'context>, filename: &CStr, codegen: LLVMCodeGenFileType) -> Result<(), Owned<MallocCStr>> {
+    pub fn triple(&self) -> Owned<MallocCStr> {
+        unsafe {
+            MallocCStr::from_raw(LLVMGetTargetMachineTriple(self.as_raw()))
+        }
+    }
+
+    pub fn cpu(&self) -> Owned<MallocCStr> {
+        unsafe {
+            MallocCStr::from_raw(LLVMGetTargetMachineCPU(self.as_raw()))
+        }
+    }
+
+    pub fn features(&self) -> Owned<MallocCStr> {
+        unsafe {
+            MallocCStr::from_raw(LLVMGetTargetMachineFeatureString(self.as_raw()))
+        }
+    }
+
+    /// Adds the target's analyses, like target transform info, so that optimization passes can
+    /// make target specific decisions.
+    pub fn add_analysis_passes<'mid>(&self, pass_manager: &mut FunctionPassManager<'mid>) {
+        unsafe {
+            LLVMAddAnalysisPasses(self.as_raw(), pass_manager.as_raw());
+        }
+    }
+
+    pub fn emit_module_to_file<'cid, 'mid, 'context>(&self, module: &Module<'cid, 'mid, 'context>, filename: &CStr, file_type: CodeGenFileType) -> Result<(), Owned<MallocCStr>> {
         unsafe {
             let mut err_ptr = mem::MaybeUninit::uninit();
-            if LLVMTargetMachineEmitToFile(self.as_raw(), module.as_raw(), filename.as_ptr() as *mut _, codegen, err_ptr.as_mut_ptr()) == 0 {
+            if LLVMTargetMachineEmitToFile(self.as_raw(), module.as_raw(), filename.as_ptr() as *mut _, file_type.as_raw(), err_ptr.as_mut_ptr()) == 0 {
                 Ok(())
             } else {
                 Err(MallocCStr::from_raw(err_ptr.assume_init()))
@@ -73,6 +234,18 @@ impl TargetMachine {
         }
     }
 
+    pub fn emit_module_to_memory<'cid, 'mid, 'context>(&self, module: &Module<'cid, 'mid, 'context>, file_type: CodeGenFileType) -> Result<Owned<MemoryBuffer>, Owned<MallocCStr>> {
+        unsafe {
+            let mut buffer_ptr = mem::MaybeUninit::uninit();
+            let mut err_ptr = mem::MaybeUninit::uninit();
+            if LLVMTargetMachineEmitToMemoryBuffer(self.as_raw(), module.as_raw(), file_type.as_raw(), err_ptr.as_mut_ptr(), buffer_ptr.as_mut_ptr()) == 0 {
+                Ok(Owned::from_raw(buffer_ptr.assume_init() as *mut MemoryBuffer))
+            } else {
+                Err(MallocCStr::from_raw(err_ptr.assume_init()))
+            }
+        }
+    }
+
     pub fn data_layout(&self) -> Owned<DataLayout> {
         unsafe {
             Owned::from_raw(LLVMCreateTargetDataLayout(self.as_raw()) as *mut DataLayout)
@@ -84,6 +257,71 @@ impl TargetMachine {
     }
 }
 
+pub struct TargetMachineBuilder<'a> {
+    init: TargetsInitialized,
+    triple: Option<&'a str>,
+    cpu: &'a str,
+    features: &'a str,
+    opt_level: OptLevel,
+    reloc_mode: RelocMode,
+    code_model: CodeModel
+}
+
+impl<'a> TargetMachineBuilder<'a> {
+    pub fn triple(&mut self, triple: &'a str) -> &mut TargetMachineBuilder<'a> {
+        self.triple = Some(triple);
+        self
+    }
+
+    pub fn cpu(&mut self, cpu: &'a str) -> &mut TargetMachineBuilder<'a> {
+        self.cpu = cpu;
+        self
+    }
+
+    /// Sets the enabled features, as a comma separated list like `+sse2,-avx`.
+    pub fn features(&mut self, features: &'a str) -> &mut TargetMachineBuilder<'a> {
+        self.features = features;
+        self
+    }
+
+    pub fn opt_level(&mut self, opt_level: OptLevel) -> &mut TargetMachineBuilder<'a> {
+        self.opt_level = opt_level;
+        self
+    }
+
+    pub fn reloc_mode(&mut self, reloc_mode: RelocMode) -> &mut TargetMachineBuilder<'a> {
+        self.reloc_mode = reloc_mode;
+        self
+    }
+
+    pub fn code_model(&mut self, code_model: CodeModel) -> &mut TargetMachineBuilder<'a> {
+        self.code_model = code_model;
+        self
+    }
+
+    /// Creates the target machine, failing if there is no target for the triple.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the triple, CPU or features contain a nul byte.
+    pub fn build(&self) -> Result<Owned<TargetMachine>, Owned<MallocCStr>> {
+        let triple = match self.triple {
+            Some(triple) => normalize_triple(&CString::new(triple).expect("triple contains a nul byte")),
+            None => default_triple()
+        };
+        let cpu = CString::new(self.cpu).expect("CPU name contains a nul byte");
+        let features = CString::new(self.features).expect("features contain a nul byte");
+
+        let target = Target::from_triple(self.init, &triple)?;
+        unsafe {
+            Ok(Owned::from_raw(LLVMCreateTargetMachine(
+                target.as_raw(), triple.as_ptr(), cpu.as_ptr(), features.as_ptr(),
+                self.opt_level.as_raw(), self.reloc_mode.as_raw(), self.code_model.as_raw()
+            ) as *mut TargetMachine))
+        }
+    }
+}
+
 pub struct DataLayout {
     _opaque: Opaque
 }
@@ -107,7 +345,258 @@ impl DataLayout {
         }
     }
 
+    pub fn byte_order(&self) -> ByteOrder {
+        unsafe {
+            ByteOrder::from_raw(LLVMByteOrder(self.as_raw()))
+        }
+    }
+
+    /// The size of a pointer in the given address space, in bytes.
+    pub fn pointer_size(&self, address_space: c_uint) -> c_uint {
+        unsafe {
+            LLVMPointerSizeForAS(self.as_raw(), address_space)
+        }
+    }
+
+    /// The integer type with the same size as a pointer in the default address space.
+    pub fn int_ptr_type<'ctx, 'cid>(&self, context: &'ctx Context<'cid>) -> &'ctx IntegerType<'cid> {
+        unsafe {
+            &*(LLVMIntPtrTypeInContext(context.as_raw(), self.as_raw()) as *mut IntegerType)
+        }
+    }
+
+    /// The maximum number of bytes that may be overwritten by storing a value of type `ty`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ty` is unsized, like `void` or an opaque struct.
+    pub fn store_size_of<'cid, Ty: DerivesFrom<Type<'cid>> + ?Sized>(&self, ty: &Ty) -> c_ulonglong {
+        unsafe {
+            LLVMStoreSizeOfType(self.as_raw(), sized_raw(ty))
+        }
+    }
+
+    /// The offset in bytes between successive values of type `ty` in memory, including padding.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ty` is unsized, like `void` or an opaque struct.
+    pub fn abi_size_of<'cid, Ty: DerivesFrom<Type<'cid>> + ?Sized>(&self, ty: &Ty) -> c_ulonglong {
+        unsafe {
+            LLVMABISizeOfType(self.as_raw(), sized_raw(ty))
+        }
+    }
+
+    /// The minimum alignment in bytes required for values of type `ty` by the ABI.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ty` is unsized, like `void` or an opaque struct.
+    pub fn abi_alignment_of<'cid, Ty: DerivesFrom<Type<'cid>> + ?Sized>(&self, ty: &Ty) -> c_uint {
+        unsafe {
+            LLVMABIAlignmentOfType(self.as_raw(), sized_raw(ty))
+        }
+    }
+
+    /// The alignment in bytes preferred for values of type `ty`, which may be larger than the ABI
+    /// alignment.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ty` is unsized, like `void` or an opaque struct.
+    pub fn preferred_alignment_of<'cid, Ty: DerivesFrom<Type<'cid>> + ?Sized>(&self, ty: &Ty) -> c_uint {
+        unsafe {
+            LLVMPreferredAlignmentOfType(self.as_raw(), sized_raw(ty))
+        }
+    }
+
+    /// The offset in bytes of field `index` from the start of a value of type `ty`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ty` is opaque or has no field `index`.
+    pub fn offset_of_element<'cid>(&self, ty: &StructType<'cid>, index: c_uint) -> c_ulonglong {
+        assert!(index < ty.element_count(), "struct field index out of bounds");
+        unsafe {
+            LLVMOffsetOfElement(self.as_raw(), sized_raw(ty), index)
+        }
+    }
+
+    /// The index of the field of `ty` that contains the byte at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ty` is opaque or `offset` is not less than its ABI size.
+    pub fn element_at_offset<'cid>(&self, ty: &StructType<'cid>, offset: c_ulonglong) -> c_uint {
+        assert!(offset < self.abi_size_of(ty), "offset is past the end of the struct");
+        unsafe {
+            LLVMElementAtOffset(self.as_raw(), sized_raw(ty), offset)
+        }
+    }
+
     pub fn as_raw(&self) -> LLVMTargetDataRef {
         self as *const DataLayout as LLVMTargetDataRef
     }
 }
+
+/// How hard code generation tries to optimize.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum OptLevel {
+    None,
+    Less,
+    Default,
+    Aggressive
+}
+
+impl OptLevel {
+    pub fn from_raw(raw: LLVMCodeGenOptLevel) -> OptLevel {
+        match raw {
+            LLVMCodeGenOptLevel::LLVMCodeGenLevelNone => OptLevel::None,
+            LLVMCodeGenOptLevel::LLVMCodeGenLevelLess => OptLevel::Less,
+            LLVMCodeGenOptLevel::LLVMCodeGenLevelDefault => OptLevel::Default,
+            LLVMCodeGenOptLevel::LLVMCodeGenLevelAggressive => OptLevel::Aggressive
+        }
+    }
+
+    pub fn as_raw(self) -> LLVMCodeGenOptLevel {
+        match self {
+            OptLevel::None => LLVMCodeGenOptLevel::LLVMCodeGenLevelNone,
+            OptLevel::Less => LLVMCodeGenOptLevel::LLVMCodeGenLevelLess,
+            OptLevel::Default => LLVMCodeGenOptLevel::LLVMCodeGenLevelDefault,
+            OptLevel::Aggressive => LLVMCodeGenOptLevel::LLVMCodeGenLevelAggressive
+        }
+    }
+}
+
+/// The relocation model, which determines whether generated code must be position independent.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RelocMode {
+    /// The target's default.
+    Default,
+    Static,
+    PIC,
+    DynamicNoPIC,
+    /// Read-only data is addressed relative to the program counter.
+    ROPI,
+    /// Read-write data is addressed relative to a static base register.
+    RWPI,
+    ROPIRWPI
+}
+
+impl RelocMode {
+    pub fn from_raw(raw: LLVMRelocMode) -> RelocMode {
+        match raw {
+            LLVMRelocMode::LLVMRelocDefault => RelocMode::Default,
+            LLVMRelocMode::LLVMRelocStatic => RelocMode::Static,
+            LLVMRelocMode::LLVMRelocPIC => RelocMode::PIC,
+            LLVMRelocMode::LLVMRelocDynamicNoPic => RelocMode::DynamicNoPIC,
+            LLVMRelocMode::LLVMRelocROPI => RelocMode::ROPI,
+            LLVMRelocMode::LLVMRelocRWPI => RelocMode::RWPI,
+            LLVMRelocMode::LLVMRelocROPI_RWPI => RelocMode::ROPIRWPI
+        }
+    }
+
+    pub fn as_raw(self) -> LLVMRelocMode {
+        match self {
+            RelocMode::Default => LLVMRelocMode::LLVMRelocDefault,
+            RelocMode::Static => LLVMRelocMode::LLVMRelocStatic,
+            RelocMode::PIC => LLVMRelocMode::LLVMRelocPIC,
+            RelocMode::DynamicNoPIC => LLVMRelocMode::LLVMRelocDynamicNoPic,
+            RelocMode::ROPI => LLVMRelocMode::LLVMRelocROPI,
+            RelocMode::RWPI => LLVMRelocMode::LLVMRelocRWPI,
+            RelocMode::ROPIRWPI => LLVMRelocMode::LLVMRelocROPI_RWPI
+        }
+    }
+}
+
+/// The code model, which limits how far apart code and data may be placed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CodeModel {
+    /// The target's default.
+    Default,
+    /// The target's default for JIT compilation.
+    JITDefault,
+    Tiny,
+    Small,
+    Kernel,
+    Medium,
+    Large
+}
+
+impl CodeModel {
+    pub fn from_raw(raw: LLVMCodeModel) -> CodeModel {
+        match raw {
+            LLVMCodeModel::LLVMCodeModelDefault => CodeModel::Default,
+            LLVMCodeModel::LLVMCodeModelJITDefault => CodeModel::JITDefault,
+            LLVMCodeModel::LLVMCodeModelTiny => CodeModel::Tiny,
+            LLVMCodeModel::LLVMCodeModelSmall => CodeModel::Small,
+            LLVMCodeModel::LLVMCodeModelKernel => CodeModel::Kernel,
+            LLVMCodeModel::LLVMCodeModelMedium => CodeModel::Medium,
+            LLVMCodeModel::LLVMCodeModelLarge => CodeModel::Large
+        }
+    }
+
+    pub fn as_raw(self) -> LLVMCodeModel {
+        match self {
+            CodeModel::Default => LLVMCodeModel::LLVMCodeModelDefault,
+            CodeModel::JITDefault => LLVMCodeModel::LLVMCodeModelJITDefault,
+            CodeModel::Tiny => LLVMCodeModel::LLVMCodeModelTiny,
+            CodeModel::Small => LLVMCodeModel::LLVMCodeModelSmall,
+            CodeModel::Kernel => LLVMCodeModel::LLVMCodeModelKernel,
+            CodeModel::Medium => LLVMCodeModel::LLVMCodeModelMedium,
+            CodeModel::Large => LLVMCodeModel::LLVMCodeModelLarge
+        }
+    }
+}
+
+/// The kind of file produced by code generation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CodeGenFileType {
+    Assembly,
+    Object
+}
+
+impl CodeGenFileType {
+    pub fn from_raw(raw: LLVMCodeGenFileType) -> CodeGenFileType {
+        match raw {
+            LLVMCodeGenFileType::LLVMAssemblyFile => CodeGenFileType::Assembly,
+            LLVMCodeGenFileType::LLVMObjectFile => CodeGenFileType::Object
+        }
+    }
+
+    pub fn as_raw(self) -> LLVMCodeGenFileType {
+        match self {
+            CodeGenFileType::Assembly => LLVMCodeGenFileType::LLVMAssemblyFile,
+            CodeGenFileType::Object => LLVMCodeGenFileType::LLVMObjectFile
+        }
+    }
+}
+
+/// The order in which the bytes of a multi-byte value are stored in memory.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ByteOrder {
+    BigEndian,
+    LittleEndian
+}
+
+impl ByteOrder {
+    pub fn from_raw(raw: LLVMByteOrdering) -> ByteOrder {
+        match raw {
+            LLVMByteOrdering::LLVMBigEndian => ByteOrder::BigEndian,
+            LLVMByteOrdering::LLVMLittleEndian => ByteOrder::LittleEndian
+        }
+    }
+
+    pub fn as_raw(self) -> LLVMByteOrdering {
+        match self {
+            ByteOrder::BigEndian => LLVMByteOrdering::LLVMBigEndian,
+            ByteOrder::LittleEndian => LLVMByteOrdering::LLVMLittleEndian
+        }
+    }
+}
+
+fn sized_raw<'cid, Ty: DerivesFrom<Type<'cid>> + ?Sized>(ty: &Ty) -> LLVMTypeRef {
+    let raw = upcast::<_,Type>(ty).as_raw();
+    assert!(unsafe { LLVMTypeIsSized(raw) } != 0, "type is not sized");
+    raw
+}
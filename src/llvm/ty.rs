@@ -3,6 +3,7 @@ use llvm_sys::core::*;
 
 use libc::{c_int, c_uint};
 
+use std::ffi::CStr;
 use std::marker::PhantomData;
 use std::mem::transmute_copy;
 
@@ -12,6 +13,7 @@ use inheritance::{upcast, DerivesFrom};
 
 use llvm::value::Value;
 use llvm::context::Context;
+use llvm::signature::{Dynamic, ArgTypes, StaticReturnMarker};
 
 pub struct Type<'cid> {
     _context_id: IdRef<'cid>,
@@ -33,11 +35,28 @@ pub struct ArrayType<'cid, SubType: ?Sized> {
 unsafe impl<'cid, SubTypeSpecific: DerivesFrom<SubTypeGeneral> + ?Sized, SubTypeGeneral: ?Sized> DerivesFrom<ArrayType<'cid, SubTypeGeneral>> for ArrayType<'cid, SubTypeSpecific> { }
 unsafe impl<'cid, General: ?Sized, SubType: ?Sized> DerivesFrom<General> for ArrayType<'cid, SubType> where Type<'cid>: DerivesFrom<General> { }
 
-pub struct FunctionType<'cid> {
+pub struct VectorType<'cid, SubType: ?Sized> {
+    _inner: PhantomData<SubType>,
+    _super: Type<'cid>
+}
+unsafe impl<'cid, SubTypeSpecific: DerivesFrom<SubTypeGeneral> + ?Sized, SubTypeGeneral: ?Sized> DerivesFrom<VectorType<'cid, SubTypeGeneral>> for VectorType<'cid, SubTypeSpecific> { }
+unsafe impl<'cid, General: ?Sized, SubType: ?Sized> DerivesFrom<General> for VectorType<'cid, SubType> where Type<'cid>: DerivesFrom<General> { }
+
+pub struct StructType<'cid> {
     _super: Type<'cid>
 }
-unsafe impl<'cid> DerivesFrom<FunctionType<'cid>> for FunctionType<'cid> { }
-unsafe impl<'cid, General: ?Sized> DerivesFrom<General> for FunctionType<'cid> where Type<'cid>: DerivesFrom<General> { }
+unsafe impl<'cid> DerivesFrom<StructType<'cid>> for StructType<'cid> { }
+unsafe impl<'cid, General: ?Sized> DerivesFrom<General> for StructType<'cid> where Type<'cid>: DerivesFrom<General> { }
+
+/// A function type. `Args` and `Ret` are markers from the `signature` module describing the
+/// parameter and return types, or `Dynamic` if they are only known at runtime.
+pub struct FunctionType<'cid, Args: ?Sized = Dynamic, Ret: ?Sized = Dynamic> {
+    _signature: PhantomData<(PhantomData<Args>, PhantomData<Ret>)>,
+    _super: Type<'cid>
+}
+unsafe impl<'cid, Args: ?Sized, Ret: ?Sized> DerivesFrom<FunctionType<'cid, Args, Ret>> for FunctionType<'cid, Args, Ret> { }
+unsafe impl<'cid, Args: ArgTypes<'cid>, Ret: StaticReturnMarker<'cid>> DerivesFrom<FunctionType<'cid>> for FunctionType<'cid, Args, Ret> { }
+unsafe impl<'cid, General: ?Sized, Args: ?Sized, Ret: ?Sized> DerivesFrom<General> for FunctionType<'cid, Args, Ret> where Type<'cid>: DerivesFrom<General> { }
 
 pub struct IntegerType<'cid> {
     _super: Type<'cid>
@@ -45,6 +64,158 @@ pub struct IntegerType<'cid> {
 unsafe impl<'cid> DerivesFrom<IntegerType<'cid>> for IntegerType<'cid> { }
 unsafe impl<'cid, General: ?Sized> DerivesFrom<General> for IntegerType<'cid> where Type<'cid>: DerivesFrom<General> { }
 
+/// An integer type whose bit width is tracked statically by `Width`, one of the markers `I1`,
+/// `I8`, `I16`, `I32`, `I64` or `I128`.
+pub struct Int<'cid, Width: ?Sized> {
+    _width: PhantomData<Width>,
+    _super: IntegerType<'cid>
+}
+unsafe impl<'cid, Width: ?Sized> DerivesFrom<Int<'cid, Width>> for Int<'cid, Width> { }
+unsafe impl<'cid, General: ?Sized, Width: ?Sized> DerivesFrom<General> for Int<'cid, Width> where IntegerType<'cid>: DerivesFrom<General> { }
+
+/// A statically known integer bit width, used as the `Width` parameter of `Int`.
+///
+/// # Safety
+///
+/// `BITS` must be a width LLVM supports, between 1 and 2<sup>24</sup> - 1 inclusive.
+pub unsafe trait IntWidth {
+    const BITS: c_uint;
+}
+
+macro_rules! int_width_impl {
+    ( $($name:ident, $bits:expr)* ) => { $(
+        pub enum $name { }
+
+        unsafe impl IntWidth for $name {
+            const BITS: c_uint = $bits;
+        }
+    )* };
+}
+
+int_width_impl!{
+    I1, 1
+    I8, 8
+    I16, 16
+    I32, 32
+    I64, 64
+    I128, 128
+}
+
+/// Types that integer arithmetic and comparisons can be performed on. `Type` itself is included
+/// so that frontends that don't track types statically can still build instructions.
+///
+/// `IntegerType` is deliberately not an operand type, since it does not record the width: both
+/// sides of an operation must have the same `Int` type.
+///
+/// ```
+/// # use llvm_safe::id;
+/// # use llvm_safe::llvm::{Context, Constant, I32};
+/// id::with(|context_id| {
+///     let context = Context::new(context_id);
+///     let lhs = Constant::int::<I32>(2, &context);
+///     let rhs = Constant::int::<I32>(3, &context);
+///     lhs.add(rhs);
+/// });
+/// ```
+///
+/// ```compile_fail
+/// # use llvm_safe::id;
+/// # use llvm_safe::llvm::{Context, Constant, I32, I64};
+/// id::with(|context_id| {
+///     let context = Context::new(context_id);
+///     let lhs = Constant::int::<I32>(2, &context);
+///     let rhs = Constant::int::<I64>(3, &context);
+///     lhs.add(rhs);
+/// });
+/// ```
+///
+/// # Safety
+///
+/// `Bool` must be the type of the result of `icmp` on two values of type `Self`.
+pub unsafe trait IntOperand<'cid>: DerivesFrom<Type<'cid>> {
+    /// The type produced by comparing two values of this type with `icmp`.
+    type Bool: DerivesFrom<Type<'cid>> + ?Sized;
+}
+
+unsafe impl<'cid> IntOperand<'cid> for Type<'cid> {
+    type Bool = Type<'cid>;
+}
+
+unsafe impl<'cid, Width: IntWidth + ?Sized> IntOperand<'cid> for Int<'cid, Width> {
+    type Bool = Int<'cid, I1>;
+}
+
+pub struct FloatType<'cid> {
+    _super: Type<'cid>
+}
+unsafe impl<'cid> DerivesFrom<FloatType<'cid>> for FloatType<'cid> { }
+unsafe impl<'cid, General: ?Sized> DerivesFrom<General> for FloatType<'cid> where Type<'cid>: DerivesFrom<General> { }
+
+/// A floating point type whose format is tracked statically by `Kind`, one of the markers `F16`,
+/// `F32`, `F64`, `X86FP80`, `FP128` or `PPCFP128`.
+pub struct Float<'cid, Kind: ?Sized> {
+    _kind: PhantomData<Kind>,
+    _super: FloatType<'cid>
+}
+unsafe impl<'cid, Kind: ?Sized> DerivesFrom<Float<'cid, Kind>> for Float<'cid, Kind> { }
+unsafe impl<'cid, General: ?Sized, Kind: ?Sized> DerivesFrom<General> for Float<'cid, Kind> where FloatType<'cid>: DerivesFrom<General> { }
+
+/// A statically known floating point format, used as the `Kind` parameter of `Float`.
+///
+/// # Safety
+///
+/// Given a valid context, `raw_type` must return the floating point type in that context that
+/// `Self` stands for.
+pub unsafe trait FloatKind {
+    /// Gets the type in `context`.
+    ///
+    /// # Safety
+    ///
+    /// `context` must be a valid context.
+    unsafe fn raw_type(context: LLVMContextRef) -> LLVMTypeRef;
+}
+
+macro_rules! float_kind_impl {
+    ( $($name:ident, $c_name:ident)* ) => { $(
+        pub enum $name { }
+
+        unsafe impl FloatKind for $name {
+            unsafe fn raw_type(context: LLVMContextRef) -> LLVMTypeRef {
+                $c_name(context)
+            }
+        }
+    )* };
+}
+
+float_kind_impl!{
+    F16, LLVMHalfTypeInContext
+    F32, LLVMFloatTypeInContext
+    F64, LLVMDoubleTypeInContext
+    X86FP80, LLVMX86FP80TypeInContext
+    FP128, LLVMFP128TypeInContext
+    PPCFP128, LLVMPPCFP128TypeInContext
+}
+
+/// Types that floating point arithmetic and comparisons can be performed on. As with
+/// `IntOperand`, `Type` itself is included for frontends that don't track types statically, and
+/// `FloatType` is not an operand type since it does not record the format.
+///
+/// # Safety
+///
+/// `Bool` must be the type of the result of `fcmp` on two values of type `Self`.
+pub unsafe trait FloatOperand<'cid>: DerivesFrom<Type<'cid>> {
+    /// The type produced by comparing two values of this type with `fcmp`.
+    type Bool: DerivesFrom<Type<'cid>> + ?Sized;
+}
+
+unsafe impl<'cid> FloatOperand<'cid> for Type<'cid> {
+    type Bool = Type<'cid>;
+}
+
+unsafe impl<'cid, Kind: FloatKind + ?Sized> FloatOperand<'cid> for Float<'cid, Kind> {
+    type Bool = Int<'cid, I1>;
+}
+
 impl<'cid> Type<'cid> {
     pub fn dump(&self) {
         unsafe {
@@ -56,15 +227,45 @@ impl<'cid> Type<'cid> {
         self as *const Type as *mut Type as LLVMTypeRef
     }
 
-    pub fn f32<'ctx>(context: &'ctx Context<'cid>) -> &'ctx Type<'cid> {
+    pub fn half<'ctx>(context: &'ctx Context<'cid>) -> &'ctx FloatType<'cid> {
+        unsafe {
+            &*(LLVMHalfTypeInContext(context.as_raw()) as *mut FloatType)
+        }
+    }
+
+    pub fn f32<'ctx>(context: &'ctx Context<'cid>) -> &'ctx FloatType<'cid> {
+        unsafe {
+            &*(LLVMFloatTypeInContext(context.as_raw()) as *mut FloatType)
+        }
+    }
+
+    pub fn f64<'ctx>(context: &'ctx Context<'cid>) -> &'ctx FloatType<'cid> {
+        unsafe {
+            &*(LLVMDoubleTypeInContext(context.as_raw()) as *mut FloatType)
+        }
+    }
+
+    pub fn x86_fp80<'ctx>(context: &'ctx Context<'cid>) -> &'ctx FloatType<'cid> {
+        unsafe {
+            &*(LLVMX86FP80TypeInContext(context.as_raw()) as *mut FloatType)
+        }
+    }
+
+    pub fn fp128<'ctx>(context: &'ctx Context<'cid>) -> &'ctx FloatType<'cid> {
+        unsafe {
+            &*(LLVMFP128TypeInContext(context.as_raw()) as *mut FloatType)
+        }
+    }
+
+    pub fn ppc_fp128<'ctx>(context: &'ctx Context<'cid>) -> &'ctx FloatType<'cid> {
         unsafe {
-            &*(LLVMFloatTypeInContext(context.as_raw()) as *mut Type)
+            &*(LLVMPPCFP128TypeInContext(context.as_raw()) as *mut FloatType)
         }
     }
 
-    pub fn f64<'ctx>(context: &'ctx Context<'cid>) -> &'ctx Type<'cid> {
+    pub fn float<'ctx, Kind: FloatKind + ?Sized>(context: &'ctx Context<'cid>) -> &'ctx Float<'cid, Kind> {
         unsafe {
-            &*(LLVMDoubleTypeInContext(context.as_raw()) as *mut Type)
+            &*(Kind::raw_type(context.as_raw()) as *mut Float<Kind>)
         }
     }
 
@@ -92,12 +293,41 @@ impl<'cid> Type<'cid> {
         }
     }
 
+    pub fn vector<'ctx, SubType: DerivesFrom<Type<'cid>> + ?Sized>(inner: &'ctx SubType, count: c_uint) -> &'ctx VectorType<'cid, SubType> {
+        unsafe {
+            &*(LLVMVectorType(upcast(inner).as_raw(), count) as *mut VectorType<SubType>)
+        }
+    }
+
+    /// Creates a literal (unnamed) struct type with the given fields.
+    pub fn struct_<'ctx>(elements: &[&'ctx Type<'cid>], packed: bool, context: &'ctx Context<'cid>) -> &'ctx StructType<'cid> {
+        unsafe {
+            &*(LLVMStructTypeInContext(context.as_raw(), elements.as_ptr() as *mut LLVMTypeRef, elements.len() as c_uint, packed as c_int) as *mut StructType)
+        }
+    }
+
+    /// Creates a new, opaque named struct type. Its fields can be filled in later with
+    /// `StructType::set_body`, which allows recursive types.
+    pub fn named_struct<'ctx>(name: &CStr, context: &'ctx Context<'cid>) -> &'ctx StructType<'cid> {
+        unsafe {
+            &*(LLVMStructCreateNamed(context.as_raw(), name.as_ptr()) as *mut StructType)
+        }
+    }
+
     pub fn function<'ctx>(params: &[&'ctx Type<'cid>], ret: &'ctx Type<'cid>, var_arg: bool) -> &'ctx FunctionType<'cid> {
         unsafe {
             &*(LLVMFunctionType(ret.as_raw(), params.as_ptr() as *mut LLVMTypeRef, params.len() as u32, var_arg as c_int) as *mut FunctionType)
         }
     }
 
+    /// Creates a statically typed function type, like `Type::typed_function::<(I32, I32), I64>`.
+    pub fn typed_function<'ctx, Args: ArgTypes<'cid>, Ret: StaticReturnMarker<'cid>>(context: &'ctx Context<'cid>) -> &'ctx FunctionType<'cid, Args, Ret> {
+        let mut params = Args::raw_types(context);
+        unsafe {
+            &*(LLVMFunctionType(upcast::<_,Type>(Ret::get(context)).as_raw(), params.as_mut_ptr(), params.len() as u32, 0) as *mut FunctionType<Args, Ret>)
+        }
+    }
+
     pub fn i1<'ctx>(context: &'ctx Context<'cid>) -> &'ctx IntegerType<'cid> {
         unsafe {
             &*(LLVMInt1TypeInContext(context.as_raw()) as *mut IntegerType)
@@ -127,9 +357,21 @@ impl<'cid> Type<'cid> {
             &*(LLVMInt64TypeInContext(context.as_raw()) as *mut IntegerType)
         }
     }
+
+    pub fn i128<'ctx>(context: &'ctx Context<'cid>) -> &'ctx IntegerType<'cid> {
+        unsafe {
+            &*(LLVMInt128TypeInContext(context.as_raw()) as *mut IntegerType)
+        }
+    }
+
+    pub fn int<'ctx, Width: IntWidth + ?Sized>(context: &'ctx Context<'cid>) -> &'ctx Int<'cid, Width> {
+        unsafe {
+            &*(LLVMIntTypeInContext(context.as_raw(), Width::BITS) as *mut Int<Width>)
+        }
+    }
 }
 
-impl<'cid> FunctionType<'cid> {
+impl<'cid, Args: ?Sized, Ret: ?Sized> FunctionType<'cid, Args, Ret> {
     pub fn is_var_arg(&self) -> bool {
         unsafe {
             LLVMIsFunctionVarArg(upcast::<_,Type>(self).as_raw()) != 0
@@ -137,6 +379,14 @@ impl<'cid> FunctionType<'cid> {
     }
 }
 
+impl<'cid> IntegerType<'cid> {
+    pub fn width(&self) -> c_uint {
+        unsafe {
+            LLVMGetIntTypeWidth(upcast::<_,Type>(self).as_raw())
+        }
+    }
+}
+
 impl<'cid, PointeeTy: ?Sized> PointerType<'cid, PointeeTy> {
     pub fn pointee_ty(&self) -> &PointeeTy {
         unsafe {
@@ -145,6 +395,68 @@ impl<'cid, PointeeTy: ?Sized> PointerType<'cid, PointeeTy> {
     }
 }
 
+impl<'cid, ElementTy: ?Sized> VectorType<'cid, ElementTy> {
+    pub fn element_ty(&self) -> &ElementTy {
+        unsafe {
+            &*transmute_copy::<_,*mut ElementTy>(&LLVMGetElementType(upcast::<_,Type>(self).as_raw()))
+        }
+    }
+
+    // LLVM does not allow empty vectors, so an `is_empty` would always return false.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> c_uint {
+        unsafe {
+            LLVMGetVectorSize(upcast::<_,Type>(self).as_raw())
+        }
+    }
+}
+
+impl<'cid> StructType<'cid> {
+    /// Sets the fields of an opaque named struct.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the struct is not opaque, i.e. if it is a literal struct or already has a body.
+    // This takes `&self` because named structs are owned by the context and only ever handed out
+    // as shared references. Mutating through one is no different from interning a new type
+    // through `&Context`: the body can be set at most once, and every query goes through LLVM
+    // rather than caching anything on the Rust side, so nothing observes a stale body.
+    pub fn set_body(&self, elements: &[&Type<'cid>], packed: bool) {
+        assert!(self.is_opaque(), "struct body already set");
+        unsafe {
+            LLVMStructSetBody(upcast::<_,Type>(self).as_raw(), elements.as_ptr() as *mut LLVMTypeRef, elements.len() as c_uint, packed as c_int)
+        }
+    }
+
+    pub fn is_opaque(&self) -> bool {
+        unsafe {
+            LLVMIsOpaqueStruct(upcast::<_,Type>(self).as_raw()) != 0
+        }
+    }
+
+    pub fn is_packed(&self) -> bool {
+        unsafe {
+            LLVMIsPackedStruct(upcast::<_,Type>(self).as_raw()) != 0
+        }
+    }
+
+    pub fn element_count(&self) -> c_uint {
+        unsafe {
+            LLVMCountStructElementTypes(upcast::<_,Type>(self).as_raw())
+        }
+    }
+
+    pub fn element_ty(&self, index: c_uint) -> Option<&Type<'cid>> {
+        if index < self.element_count() {
+            unsafe {
+                Some(&*(LLVMStructGetTypeAtIndex(upcast::<_,Type>(self).as_raw(), index) as *mut Type))
+            }
+        } else {
+            None
+        }
+    }
+}
+
 impl<'cid, ElementTy: ?Sized> ArrayType<'cid, ElementTy> {
     pub fn element_ty(&self) -> &ElementTy {
         unsafe {
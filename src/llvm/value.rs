@@ -2,11 +2,14 @@ use llvm_sys::prelude::*;
 use llvm_sys::core::*;
 
 use std::marker::PhantomData;
+use libc::c_uint;
 
 use id::IdRef;
 use inheritance::DerivesFrom;
 use opaque::Opaque;
 
+use llvm::Type;
+
 pub struct Value<'cid, 'mid, 'fid, Ty: ?Sized> {
     _context_id: IdRef<'cid>,
     _module_id: IdRef<'mid>,
@@ -24,6 +27,19 @@ impl<'cid, 'mid, 'fid, Ty: ?Sized> Value<'cid, 'mid, 'fid, Ty> {
         }
     }
 
+    /// Iterates over the places this value is used as an operand, by instructions or by constants.
+    ///
+    /// Globals are used all over their module, and constants all over their context, so the users
+    /// are branded with `'static` in place of a module and function id. No builder can have those
+    /// ids, so the users can be inspected but never used to build code. `Instruction::uses` keeps
+    /// the brands, since an instruction is only used within its own function.
+    pub fn uses<'a>(&'a self) -> ValueUses<'a, 'cid> {
+        ValueUses {
+            _value: PhantomData,
+            inner: unsafe { LLVMGetFirstUse(self.as_raw()) }
+        }
+    }
+
     pub fn dump(&self) {
         unsafe {
             LLVMDumpValue(self.as_raw());
@@ -34,3 +50,60 @@ impl<'cid, 'mid, 'fid, Ty: ?Sized> Value<'cid, 'mid, 'fid, Ty> {
         self as *const Value<Ty> as *mut Value<Ty> as LLVMValueRef
     }
 }
+
+/// One use of a value as an operand of an instruction or a constant.
+pub struct ValueUse<'cid> {
+    _context_id: IdRef<'cid>,
+    _opaque: Opaque
+}
+
+impl<'cid> ValueUse<'cid> {
+    /// The instruction or constant that has the used value as an operand.
+    pub fn user(&self) -> &Value<'cid, 'static, 'static, Type<'cid>> {
+        unsafe {
+            &*(LLVMGetUser(self.as_raw()) as *mut Value<Type>)
+        }
+    }
+
+    pub fn used_value(&self) -> &Value<'cid, 'static, 'static, Type<'cid>> {
+        unsafe {
+            &*(LLVMGetUsedValue(self.as_raw()) as *mut Value<Type>)
+        }
+    }
+
+    /// Which operand of the user this is.
+    pub fn operand_index(&self) -> usize {
+        unsafe {
+            // The C API has no getOperandNo, so search the user's operands for this use.
+            let user = LLVMGetUser(self.as_raw());
+            (0..LLVMGetNumOperands(user) as c_uint)
+                .position(|index| LLVMGetOperandUse(user, index) == self.as_raw())
+                .expect("use is not among its user's operands")
+        }
+    }
+
+    pub fn as_raw(&self) -> LLVMUseRef {
+        self as *const ValueUse as *mut ValueUse as LLVMUseRef
+    }
+}
+
+pub struct ValueUses<'a, 'cid: 'a> {
+    _value: PhantomData<&'a ValueUse<'cid>>,
+    inner: LLVMUseRef
+}
+
+impl<'a, 'cid: 'a> Iterator for ValueUses<'a, 'cid> {
+    type Item = &'a ValueUse<'cid>;
+
+    fn next(&mut self) -> Option<&'a ValueUse<'cid>> {
+        if self.inner.is_null() {
+            None
+        } else {
+            unsafe {
+                let ret = Some(&*(self.inner as *const ValueUse));
+                self.inner = LLVMGetNextUse(self.inner);
+                ret
+            }
+        }
+    }
+}
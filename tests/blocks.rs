@@ -0,0 +1,73 @@
+extern crate llvm_safe;
+#[macro_use] extern crate const_cstr_fork;
+
+use llvm_safe::id;
+use llvm_safe::llvm::{Context, Module, Builder, Type, Opcode, Void};
+
+#[test]
+fn block_and_instruction_order() {
+    id::with2(|context_id, module_id| {
+        let context = Context::new(context_id);
+        let mut module = Module::new(module_id, const_cstr!("blocks").as_cstr(), &context);
+        let module_builder = module.builder();
+        let mut builder = Builder::new(&context);
+
+        let function_ty = Type::typed_function::<(), Void>(&context);
+        let function = module_builder.add_function(const_cstr!("function").as_cstr(), function_ty);
+        id::with(|function_id| {
+            let function_builder = function.builder(function_id);
+            assert!(function_builder.first_basic_block().is_none());
+
+            let (entry_label, entry) = function_builder.append_basic_block(const_cstr!("entry").as_cstr(), &context);
+            let (middle_label, middle) = function_builder.append_basic_block(const_cstr!("middle").as_cstr(), &context);
+            let (exit_label, exit) = function_builder.append_basic_block(const_cstr!("exit").as_cstr(), &context);
+            {
+                let builder = builder.position_at_end(entry);
+                builder.alloca(Type::i32(&context), const_cstr!("slot").as_cstr());
+                builder.alloca(Type::i64(&context), const_cstr!("wide").as_cstr());
+                builder.br(middle_label);
+            }
+            builder.position_at_end(middle).br(exit_label);
+            assert!(exit.terminator().is_none());
+            builder.position_at_end(exit).ret_void();
+
+            let labels = [entry_label.as_raw(), middle_label.as_raw(), exit_label.as_raw()];
+            let forwards: Vec<_> = function_builder.basic_blocks().map(|label| label.as_raw()).collect();
+            assert_eq!(forwards, labels);
+            let backwards: Vec<_> = function_builder.basic_blocks().rev().map(|label| label.as_raw()).collect();
+            assert_eq!(backwards, [labels[2], labels[1], labels[0]]);
+            assert_eq!(function_builder.first_basic_block().unwrap().as_raw(), labels[0]);
+            assert_eq!(function_builder.last_basic_block().unwrap().as_raw(), labels[2]);
+
+            // Iterating from both ends meets in the middle without repeating a block.
+            let mut blocks = function_builder.basic_blocks();
+            assert_eq!(blocks.next().unwrap().as_raw(), labels[0]);
+            assert_eq!(blocks.next_back().unwrap().as_raw(), labels[2]);
+            assert_eq!(blocks.next().unwrap().as_raw(), labels[1]);
+            assert!(blocks.next_back().is_none());
+
+            let opcodes: Vec<_> = entry.instructions().map(|instruction| instruction.opcode()).collect();
+            assert_eq!(opcodes, [Opcode::Alloca, Opcode::Alloca, Opcode::Br]);
+            let opcodes: Vec<_> = entry.instructions().rev().map(|instruction| instruction.opcode()).collect();
+            assert_eq!(opcodes, [Opcode::Br, Opcode::Alloca, Opcode::Alloca]);
+            assert_eq!(entry.first_instruction().unwrap().opcode(), Opcode::Alloca);
+            assert_eq!(entry.last_instruction().unwrap().opcode(), Opcode::Br);
+            assert_eq!(exit.terminator().unwrap().opcode(), Opcode::Ret);
+
+            for instruction in entry.instructions() {
+                assert_eq!(instruction.parent().as_raw(), labels[0]);
+            }
+            assert_eq!(middle.parent().as_raw(), function.as_raw());
+        });
+        function.verify();
+
+        // Finished code can be walked without a builder.
+        let opcodes: Vec<_> = function.basic_blocks().map(|block| block.first_instruction().unwrap().opcode()).collect();
+        assert_eq!(opcodes, [Opcode::Alloca, Opcode::Br, Opcode::Ret]);
+        assert_eq!(function.basic_blocks().rev().count(), 3);
+        let entry = function.first_basic_block().unwrap();
+        assert_eq!(entry.instructions().count(), 3);
+        assert_eq!(entry.parent().as_raw(), function.as_raw());
+        assert_eq!(function.last_basic_block().unwrap().terminator().unwrap().opcode(), Opcode::Ret);
+    });
+}
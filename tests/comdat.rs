@@ -0,0 +1,46 @@
+extern crate llvm_safe;
+#[macro_use] extern crate const_cstr_fork;
+
+use llvm_safe::id;
+use llvm_safe::llvm::{Context, Module, Type, Constant, Linkage, ComdatSelectionKind};
+use llvm_safe::llvm::{TargetsInitialized, TargetMachine, CodeGenFileType, ObjectFile};
+
+#[test]
+fn comdat_and_alias_emission() {
+    let init = TargetsInitialized::all();
+    let machine = TargetMachine::builder(init).triple("x86_64-unknown-linux-gnu").build().unwrap();
+
+    id::with2(|context_id, module_id| {
+        let context = Context::new(context_id);
+        let mut module = Module::new(module_id, const_cstr!("comdats").as_cstr(), &context);
+        module.set_target_triple(&machine.triple());
+        {
+            let module_builder = module.builder();
+
+            let shared = module_builder.add_global(const_cstr!("shared").as_cstr(), Type::i32(&context));
+            shared.set_initializer(Constant::i32(5, &context));
+            shared.set_linkage(Linkage::LinkOnceODR);
+
+            let comdat = module_builder.get_or_insert_comdat(const_cstr!("shared").as_cstr());
+            comdat.set_selection_kind(ComdatSelectionKind::Any);
+            assert_eq!(comdat.selection_kind(), ComdatSelectionKind::Any);
+            shared.set_comdat(comdat);
+            assert!(shared.comdat().is_some());
+
+            let alias = module_builder.add_alias(const_cstr!("shared_alias").as_cstr(), shared.as_constant());
+            alias.set_linkage(Linkage::External);
+        }
+
+        let buffer = machine.emit_module_to_memory(&module, CodeGenFileType::Object).unwrap();
+        let object = ObjectFile::new(&buffer).unwrap();
+
+        // The COMDAT becomes an ELF section group holding the global's own data section.
+        let section_names: Vec<_> = object.sections().map(|section| section.name().to_owned()).collect();
+        assert!(section_names.iter().any(|name| name.to_bytes() == b".group"), "no section group in {:?}", section_names);
+        assert!(section_names.iter().any(|name| name.to_bytes() == b".data.shared"), "no COMDAT data section in {:?}", section_names);
+
+        // The alias is a second symbol at the same address as its aliasee.
+        let symbol = |name: &[u8]| object.symbols().find(|symbol| symbol.name().to_bytes() == name).expect("missing symbol");
+        assert_eq!(symbol(b"shared_alias").address(), symbol(b"shared").address());
+    });
+}
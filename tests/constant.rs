@@ -0,0 +1,92 @@
+extern crate llvm_safe;
+#[macro_use] extern crate const_cstr_fork;
+
+use llvm_safe::id;
+use llvm_safe::inheritance::upcast;
+use llvm_safe::llvm::{Context, Module, Type, Constant, I8, I32, I64, I128, F32, F64};
+
+#[test]
+fn real_from_str() {
+    id::with(|context_id| {
+        let context = Context::new(context_id);
+        let f64_ty = Type::float::<F64>(&context);
+        let parse = |text| Constant::real_from_str(text, f64_ty).map(|constant| constant.double_value().unwrap());
+
+        assert_eq!(parse("1.5e3"), Some(1500.0));
+        assert_eq!(parse("-.5"), Some(-0.5));
+        assert_eq!(parse("+2."), Some(2.0));
+        assert_eq!(parse("1E-2"), Some(0.01));
+        assert_eq!(parse("0x1p-3"), Some(0.125));
+        assert_eq!(parse("-0X1.8P1"), Some(-3.0));
+
+        for &text in &["", "abc", ".", "-", "1e", "1e+", "1.2.3", "1e5.0", "0x1", "0x.p1", "0x1p", "inf", " 1"] {
+            assert_eq!(parse(text), None, "{:?}", text);
+        }
+    });
+}
+
+#[test]
+fn integer_from_str() {
+    id::with(|context_id| {
+        let context = Context::new(context_id);
+        let i8_ty = Type::int::<I8>(&context);
+        let i128_ty = Type::int::<I128>(&context);
+
+        assert_eq!(Constant::integer_from_str("-42", 10, i8_ty).and_then(|constant| constant.sext_value()), Some(-42));
+        assert_eq!(Constant::integer_from_str("7f", 16, i8_ty).and_then(|constant| constant.zext_value()), Some(0x7f));
+        assert_eq!(Constant::integer_from_str("11111111", 2, i8_ty).and_then(|constant| constant.zext_value()), Some(0xff));
+        // Few enough digits for LLVM to accept, so the value wraps.
+        assert_eq!(Constant::integer_from_str("999", 10, i8_ty).and_then(|constant| constant.zext_value()), Some(999 % 256));
+
+        let max = Constant::integer_from_str("170141183460469231731687303715884105727", 10, i128_ty).unwrap();
+        assert_eq!(max.zext_value(), None);
+
+        for &(text, radix) in &[("", 10), ("-", 10), ("12a", 10), ("12", 7), ("12", 37), ("111111111", 2), ("fffff", 16), ("1000", 8), ("100000", 10)] {
+            assert!(Constant::integer_from_str(text, radix, i8_ty).is_none(), "{:?} in radix {}", text, radix);
+        }
+    });
+}
+
+#[test]
+fn typed_constructors() {
+    id::with(|context_id| {
+        let context = Context::new(context_id);
+
+        let sum = Constant::int::<I32>(2, &context).add(Constant::int::<I32>(-5, &context));
+        assert_eq!(sum.sext_value(), Some(-3));
+        assert_eq!(Constant::int::<I8>(300, &context).zext_value(), Some(300 % 256));
+        assert_eq!(Constant::int::<I128>(-1, &context).zext_value(), None);
+
+        let product = Constant::float::<F64>(1.5, &context).fmul(Constant::float::<F64>(4.0, &context));
+        assert_eq!(product.double_value(), Some(6.0));
+        assert_eq!(Constant::float::<F32>(0.1, &context).double_value(), Some(0.1f32 as f64));
+    });
+}
+
+#[test]
+fn global_addresses() {
+    id::with2(|context_id, module_id| {
+        let context = Context::new(context_id);
+        let mut module = Module::new(module_id, const_cstr!("constants").as_cstr(), &context);
+        let module_builder = module.builder();
+
+        let i32_ty = Type::i32(&context);
+        let pointer_ty = Type::pointer(i32_ty, 0);
+        let target = module_builder.add_global(const_cstr!("target").as_cstr(), i32_ty);
+        target.set_initializer(Constant::i32(1, &context));
+
+        // Constants built from scratch mix freely with the global's module-branded address.
+        let address = target.as_constant();
+        let table = module_builder.add_global(const_cstr!("table").as_cstr(), Type::array(pointer_ty, 2));
+        table.set_initializer(Constant::array(pointer_ty, &[address, upcast(Constant::null_pointer(pointer_ty))]));
+
+        let i64_ty = Type::int::<I64>(&context);
+        let offset = address.ptr_to_int(i64_ty).add(Constant::int::<I64>(4, &context));
+        let next = module_builder.add_global(const_cstr!("next").as_cstr(), i64_ty);
+        next.set_initializer(offset);
+
+        let alias = module_builder.add_alias(const_cstr!("alias").as_cstr(), address);
+        assert_eq!(alias.aliasee().as_raw(), address.as_raw());
+        assert_eq!(Constant::downcast_value(target.as_value()).unwrap().as_raw(), address.as_raw());
+    });
+}
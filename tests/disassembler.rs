@@ -0,0 +1,45 @@
+extern crate llvm_safe;
+#[macro_use] extern crate const_cstr_fork;
+
+use llvm_safe::llvm::{TargetsInitialized, Disassembler, AsmDialect};
+
+// push rbp; mov rbp, rsp; ret
+const CODE: &[u8] = &[0x55, 0x48, 0x89, 0xe5, 0xc3];
+
+fn disassemble(dialect: AsmDialect) -> Vec<(usize, usize, String)> {
+    let init = TargetsInitialized::all();
+    let empty = const_cstr!("").as_cstr();
+    let mut disassembler = Disassembler::new(init, const_cstr!("x86_64-unknown-linux-gnu").as_cstr(), empty, empty, dialect).unwrap();
+    disassembler.disassemble(CODE, 0x1000).map(|(offset, length, text)| {
+        // Mnemonics and operands are separated by tabs
+        (offset, length, text.split_whitespace().collect::<Vec<_>>().join(" "))
+    }).collect()
+}
+
+#[test]
+fn att_syntax() {
+    assert_eq!(disassemble(AsmDialect::ATT), vec![
+        (0, 1, "pushq %rbp".to_owned()),
+        (1, 3, "movq %rsp, %rbp".to_owned()),
+        (4, 1, "retq".to_owned())
+    ]);
+}
+
+#[test]
+fn intel_syntax() {
+    assert_eq!(disassemble(AsmDialect::Intel), vec![
+        (0, 1, "push rbp".to_owned()),
+        (1, 3, "mov rbp, rsp".to_owned()),
+        (4, 1, "ret".to_owned())
+    ]);
+}
+
+#[test]
+fn stops_at_undecodable_bytes() {
+    let init = TargetsInitialized::all();
+    let empty = const_cstr!("").as_cstr();
+    let mut disassembler = Disassembler::new(init, const_cstr!("x86_64-unknown-linux-gnu").as_cstr(), empty, empty, AsmDialect::ATT).unwrap();
+    // A lone REX prefix cannot start a complete instruction.
+    let lengths: Vec<_> = disassembler.disassemble(&[0x55, 0x48], 0).map(|(_, length, _)| length).collect();
+    assert_eq!(lengths, vec![1]);
+}
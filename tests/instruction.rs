@@ -0,0 +1,287 @@
+extern crate llvm_safe;
+#[macro_use] extern crate const_cstr_fork;
+
+use llvm_safe::id;
+use llvm_safe::inheritance::upcast;
+use llvm_safe::llvm::{Context, Module, Builder, Type, Constant, BasicBlock, Instruction, Opcode, InstructionKind, LLVMIntPredicate, I32, I64};
+
+#[test]
+fn classify() {
+    id::with2(|context_id, module_id| {
+        let context = Context::new(context_id);
+        let mut module = Module::new(module_id, const_cstr!("instructions").as_cstr(), &context);
+        let module_builder = module.builder();
+        let mut builder = Builder::new(&context);
+
+        let function_ty = Type::typed_function::<(I32,), I32>(&context);
+        let function = module_builder.add_function(const_cstr!("function").as_cstr(), function_ty);
+        id::with(|function_id| {
+            let function_builder = function.builder(function_id);
+            let (arg,) = function_builder.typed_params();
+            let (_, entry) = function_builder.append_basic_block(const_cstr!("entry").as_cstr(), &context);
+            let (then_label, then_block) = function_builder.append_basic_block(const_cstr!("then").as_cstr(), &context);
+            let (else_label, else_block) = function_builder.append_basic_block(const_cstr!("else").as_cstr(), &context);
+            let i32_ty = Type::int::<I32>(&context);
+            {
+                let builder = builder.position_at_end(entry);
+                let slot = builder.alloca(i32_ty, const_cstr!("slot").as_cstr());
+                builder.store(upcast(arg), upcast(slot.as_value()));
+                let loaded = builder.load(i32_ty, upcast(slot.as_value()), const_cstr!("loaded").as_cstr());
+                let less = builder.icmp(LLVMIntPredicate::LLVMIntSLT, loaded, upcast(arg), const_cstr!("less").as_cstr());
+                builder.cond_br(upcast(less), then_label, else_label);
+            }
+            {
+                let builder = builder.position_at_end(then_block);
+                let sum = builder.add(arg, arg, const_cstr!("sum").as_cstr());
+                builder.ret(upcast(sum));
+            }
+            builder.position_at_end(else_block).br(then_label);
+
+            let mut entry_instructions = entry.instructions();
+            let alloca = entry_instructions.next().unwrap();
+            match alloca.classify() {
+                InstructionKind::Alloca(_) => {},
+                _ => panic!("expected an alloca")
+            }
+            match entry_instructions.next().unwrap().classify() {
+                InstructionKind::Store { value, pointer, volatile } => {
+                    assert_eq!(value.as_raw(), arg.as_raw());
+                    assert_eq!(pointer.as_raw(), alloca.as_raw());
+                    assert!(!volatile);
+                },
+                _ => panic!("expected a store")
+            }
+            let load = entry_instructions.next().unwrap();
+            match load.classify() {
+                InstructionKind::Load { pointer, volatile } => {
+                    assert_eq!(pointer.as_raw(), alloca.as_raw());
+                    assert!(!volatile);
+                },
+                _ => panic!("expected a load")
+            }
+            let icmp = entry_instructions.next().unwrap();
+            match icmp.classify() {
+                InstructionKind::ICmp { predicate, lhs, rhs } => {
+                    assert_eq!(predicate, LLVMIntPredicate::LLVMIntSLT);
+                    assert_eq!(lhs.as_raw(), load.as_raw());
+                    assert_eq!(rhs.as_raw(), arg.as_raw());
+                },
+                _ => panic!("expected an icmp")
+            }
+            match entry_instructions.next().unwrap().classify() {
+                InstructionKind::CondBranch { condition, then_block, else_block } => {
+                    assert_eq!(condition.as_raw(), icmp.as_raw());
+                    assert_eq!(then_block.as_raw(), then_label.as_raw());
+                    assert_eq!(else_block.as_raw(), else_label.as_raw());
+                },
+                _ => panic!("expected a conditional branch")
+            }
+            assert!(entry_instructions.next().is_none());
+
+            let sum = then_block.first_instruction().unwrap();
+            match sum.classify() {
+                InstructionKind::Other(opcode) => assert_eq!(opcode, Opcode::Add),
+                _ => panic!("expected an add")
+            }
+            let operands: Vec<_> = sum.operands().map(|operand| operand.as_raw()).collect();
+            assert_eq!(operands, [arg.as_raw(), arg.as_raw()]);
+            assert_eq!(sum.operand(1).unwrap().as_raw(), arg.as_raw());
+            assert!(sum.operand(2).is_none());
+            match then_block.terminator().unwrap().classify() {
+                InstructionKind::Return { value } => assert_eq!(value.unwrap().as_raw(), sum.as_raw()),
+                _ => panic!("expected a return")
+            }
+
+            match else_block.terminator().unwrap().classify() {
+                InstructionKind::Branch { destination } => assert_eq!(destination.as_raw(), then_label.as_raw()),
+                _ => panic!("expected a branch")
+            }
+        });
+        function.verify();
+    });
+}
+
+#[test]
+fn use_lists() {
+    id::with2(|context_id, module_id| {
+        let context = Context::new(context_id);
+        let mut module = Module::new(module_id, const_cstr!("uses").as_cstr(), &context);
+        let module_builder = module.builder();
+        let mut builder = Builder::new(&context);
+
+        let function_ty = Type::typed_function::<(I32,), I32>(&context);
+        let function = module_builder.add_function(const_cstr!("function").as_cstr(), function_ty);
+        id::with(|function_id| {
+            let function_builder = function.builder(function_id);
+            let (arg,) = function_builder.typed_params();
+            let (_, entry) = function_builder.append_basic_block(const_cstr!("entry").as_cstr(), &context);
+            {
+                let builder = builder.position_at_end(entry);
+                let doubled = builder.add(arg, arg, const_cstr!("doubled").as_cstr());
+                let quadrupled = builder.add(doubled, doubled, const_cstr!("quadrupled").as_cstr());
+                let sum = builder.add(quadrupled, doubled, const_cstr!("sum").as_cstr());
+                builder.ret(upcast(sum));
+            }
+
+            {
+                let instructions: Vec<_> = entry.instructions().collect();
+                let (doubled, quadrupled, sum) = (instructions[0], instructions[1], instructions[2]);
+                let mut uses: Vec<_> = doubled.uses().map(|use_| {
+                    assert_eq!(use_.used_value().as_raw(), doubled.as_raw());
+                    (use_.user().as_raw(), use_.operand_index())
+                }).collect();
+                uses.sort();
+                let mut expected = vec![(quadrupled.as_raw(), 0), (quadrupled.as_raw(), 1), (sum.as_raw(), 1)];
+                expected.sort();
+                assert_eq!(uses, expected);
+                assert_eq!(sum.uses().count(), 1);
+            }
+
+            assert_eq!(arg.uses().count(), 2);
+            let doubled = entry.instructions_mut().next().unwrap();
+            assert!(doubled.downcast(Type::int::<I64>(&context)).is_none());
+            doubled.downcast(Type::int::<I32>(&context)).unwrap().replace_all_uses_with(arg);
+            assert_eq!(doubled.instruction().uses().count(), 0);
+            assert_eq!(arg.uses().count(), 5);
+
+            let quadrupled = entry.instructions().nth(1).unwrap();
+            let operands: Vec<_> = quadrupled.operands().map(|operand| operand.as_raw()).collect();
+            assert_eq!(operands, [arg.as_raw(), arg.as_raw()]);
+        });
+        function.verify();
+    });
+}
+
+#[test]
+fn global_and_constant_uses() {
+    id::with2(|context_id, module_id| {
+        let context = Context::new(context_id);
+        let mut module = Module::new(module_id, const_cstr!("uses").as_cstr(), &context);
+        let module_builder = module.builder();
+        let mut builder = Builder::new(&context);
+
+        let value = module_builder.add_global(const_cstr!("value").as_cstr(), Type::int::<I32>(&context));
+        value.set_initializer(Constant::int::<I32>(7, &context));
+        let pointer = module_builder.add_global(const_cstr!("pointer").as_cstr(), Type::pointer(Type::int::<I32>(&context), 0));
+        pointer.set_initializer(value.as_constant());
+        let offset = Constant::int::<I32>(12345, &context);
+
+        let function_ty = Type::typed_function::<(), I32>(&context);
+        let function = module_builder.add_function(const_cstr!("function").as_cstr(), function_ty);
+        id::with(|function_id| {
+            let function_builder = function.builder(function_id);
+            let (_, entry) = function_builder.append_basic_block(const_cstr!("entry").as_cstr(), &context);
+            let builder = builder.position_at_end(entry);
+            let first = builder.load(Type::int::<I32>(&context), upcast(value.as_value()), const_cstr!("first").as_cstr());
+            let second = builder.load(Type::int::<I32>(&context), upcast(value.as_value()), const_cstr!("second").as_cstr());
+            let sum = builder.add(first, second, const_cstr!("sum").as_cstr());
+            let total = builder.add(sum, offset.as_value(), const_cstr!("total").as_cstr());
+            builder.ret(upcast(total));
+        });
+        function.verify();
+
+        // The global is used by both loads and by the other global's initializer.
+        let users: Vec<_> = value.as_value().uses().map(|use_| use_.user()).collect();
+        assert_eq!(users.len(), 3);
+        assert_eq!(users.iter().filter(|user| Instruction::downcast_value(**user).is_ok()).count(), 2);
+        assert!(users.iter().any(|user| user.as_raw() == pointer.as_raw()));
+
+        let uses: Vec<_> = offset.as_value().uses().collect();
+        assert_eq!(uses.len(), 1);
+        assert_eq!(uses[0].used_value().as_raw(), offset.as_raw());
+        assert_eq!(uses[0].operand_index(), 1);
+    });
+}
+
+#[test]
+fn move_and_reinsert() {
+    id::with2(|context_id, module_id| {
+        let context = Context::new(context_id);
+        let mut module = Module::new(module_id, const_cstr!("moves").as_cstr(), &context);
+        let module_builder = module.builder();
+        let mut builder = Builder::new(&context);
+
+        let function_ty = Type::typed_function::<(I32,), I32>(&context);
+        let function = module_builder.add_function(const_cstr!("function").as_cstr(), function_ty);
+        id::with(|function_id| {
+            let function_builder = function.builder(function_id);
+            let (arg,) = function_builder.typed_params();
+            let (_, entry) = function_builder.append_basic_block(const_cstr!("entry").as_cstr(), &context);
+            let (exit_label, exit) = function_builder.append_basic_block(const_cstr!("exit").as_cstr(), &context);
+            {
+                let builder = builder.position_at_end(entry);
+                let p = builder.add(arg, arg, const_cstr!("p").as_cstr());
+                let q = builder.add(arg, arg, const_cstr!("q").as_cstr());
+                builder.add(p, q, const_cstr!("r").as_cstr());
+                builder.add(arg, arg, const_cstr!("s").as_cstr());
+                builder.add(arg, arg, const_cstr!("moved").as_cstr());
+                builder.br(exit_label);
+            }
+
+            let order = |block: &BasicBlock| block.instructions().map(|instruction| instruction.as_raw()).collect::<Vec<_>>();
+            let original = order(entry);
+            let (p, q, r, s, moved, br) = (original[0], original[1], original[2], original[3], original[4], original[5]);
+
+            {
+                let mut instructions: Vec<_> = entry.instructions_mut().collect();
+                let q_builder = instructions.remove(1);
+                q_builder.move_before(instructions[0].instruction());
+            }
+            assert_eq!(order(entry), [q, p, r, s, moved, br]);
+
+            {
+                let mut instructions: Vec<_> = entry.instructions_mut().collect();
+                let s_builder = instructions.remove(3);
+                s_builder.move_after(instructions[0].instruction());
+            }
+            assert_eq!(order(entry), [q, s, p, r, moved, br]);
+
+            {
+                let removed = entry.instructions_mut().nth(1).unwrap().remove_from_parent();
+                assert_eq!(removed.as_raw(), s);
+            }
+            // Dropping a detached instruction puts it back where it was.
+            assert_eq!(order(entry), [q, s, p, r, moved, br]);
+
+            {
+                let removed = entry.instructions_mut().nth(4).unwrap().remove_from_parent();
+                let builder = builder.position_at_end(exit);
+                let moved = builder.insert(removed, const_cstr!("moved").as_cstr());
+                builder.ret(moved.as_value());
+                assert_eq!(moved.parent().as_raw(), exit_label.as_raw());
+            }
+            assert_eq!(order(entry), [q, s, p, r, br]);
+            assert_eq!(order(exit)[0], moved);
+        });
+        function.verify();
+    });
+}
+
+#[test]
+#[should_panic(expected = "position is not in a block")]
+fn move_before_detached_position() {
+    id::with2(|context_id, module_id| {
+        let context = Context::new(context_id);
+        let mut module = Module::new(module_id, const_cstr!("moves").as_cstr(), &context);
+        let module_builder = module.builder();
+        let mut builder = Builder::new(&context);
+
+        let function_ty = Type::typed_function::<(I32,), I32>(&context);
+        let function = module_builder.add_function(const_cstr!("function").as_cstr(), function_ty);
+        id::with(|function_id| {
+            let function_builder = function.builder(function_id);
+            let (arg,) = function_builder.typed_params();
+            let (_, entry) = function_builder.append_basic_block(const_cstr!("entry").as_cstr(), &context);
+            {
+                let builder = builder.position_at_end(entry);
+                builder.add(arg, arg, const_cstr!("detached").as_cstr());
+                builder.add(arg, arg, const_cstr!("kept").as_cstr());
+            }
+
+            let mut instructions: Vec<_> = entry.instructions_mut().collect();
+            let kept = instructions.remove(1);
+            let detached = instructions.remove(0).remove_from_parent();
+            kept.move_before(detached.instruction());
+        });
+    });
+}
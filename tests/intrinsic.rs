@@ -0,0 +1,42 @@
+extern crate llvm_safe;
+#[macro_use] extern crate const_cstr_fork;
+
+use llvm_safe::id;
+use llvm_safe::inheritance::upcast;
+use llvm_safe::llvm::{Context, Module, Type, Intrinsic, F32, F64};
+
+#[test]
+fn declarations() {
+    assert!(Intrinsic::find(const_cstr!("llvm.not.an.intrinsic").as_cstr()).is_none());
+    let trap = Intrinsic::find(const_cstr!("llvm.trap").as_cstr()).unwrap();
+    let sqrt = Intrinsic::find(const_cstr!("llvm.sqrt").as_cstr()).unwrap();
+    assert_eq!(Intrinsic::find(const_cstr!("llvm.sqrt.f64").as_cstr()), Some(sqrt));
+    assert!(!trap.is_overloaded());
+    assert!(sqrt.is_overloaded());
+
+    id::with2(|context_id, module_id| {
+        let context = Context::new(context_id);
+        let mut module = Module::new(module_id, const_cstr!("intrinsics").as_cstr(), &context);
+        let module_builder = module.builder();
+
+        assert!(trap.function_type(&context).is_some());
+        assert!(sqrt.function_type(&context).is_none());
+
+        let trap_label = trap.declare(module_builder).unwrap();
+        assert_eq!(Intrinsic::of_function(trap_label), Some(trap));
+        assert_eq!(trap.declare(module_builder).unwrap().as_raw(), trap_label.as_raw());
+        assert!(sqrt.declare(module_builder).is_none());
+
+        let f64_ty = upcast(Type::f64(&context));
+        let sqrt_ty = Type::typed_function::<(F64,), F64>(&context);
+        assert!(trap.declare_overloaded(module_builder, &[f64_ty], sqrt_ty).is_none());
+        assert!(sqrt.declare_overloaded(module_builder, &[], sqrt_ty).is_none());
+        let sqrt_label = sqrt.declare_overloaded(module_builder, &[f64_ty], sqrt_ty).unwrap();
+        let named = module_builder.get_named_function(const_cstr!("llvm.sqrt.f64").as_cstr()).unwrap();
+        assert_eq!(named.as_raw(), sqrt_label.as_raw());
+        assert_eq!(Intrinsic::of_function(named), Some(sqrt));
+        assert_eq!(sqrt.declare_overloaded(module_builder, &[f64_ty], sqrt_ty).unwrap().as_raw(), sqrt_label.as_raw());
+        let sqrt_f32_ty = Type::typed_function::<(F32,), F32>(&context);
+        assert!(sqrt.declare_overloaded(module_builder, &[f64_ty], sqrt_f32_ty).is_none());
+    });
+}
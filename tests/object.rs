@@ -0,0 +1,74 @@
+extern crate llvm_safe;
+#[macro_use] extern crate const_cstr_fork;
+
+use llvm_safe::id;
+use llvm_safe::inheritance::upcast;
+use llvm_safe::llvm::{Context, Module, Builder, Type, Constant, I32};
+use llvm_safe::llvm::{TargetsInitialized, TargetMachine, RelocMode, CodeGenFileType, ObjectFile};
+
+// Relocation type numbers from the x86-64 ELF psABI.
+const R_X86_64_PLT32: u64 = 4;
+const R_X86_64_GOTPCREL: u64 = 9;
+
+#[test]
+fn sections_symbols_and_relocations() {
+    let init = TargetsInitialized::all();
+    let machine = TargetMachine::builder(init).triple("x86_64-unknown-linux-gnu").reloc_mode(RelocMode::PIC).build().unwrap();
+
+    id::with2(|context_id, module_id| {
+        let context = Context::new(context_id);
+        let mut module = Module::new(module_id, const_cstr!("objects").as_cstr(), &context);
+        module.set_target_triple(&machine.triple());
+        {
+            let module_builder = module.builder();
+            let mut builder = Builder::new(&context);
+
+            let zeroed = module_builder.add_global(const_cstr!("zeroed").as_cstr(), Type::i32(&context));
+            zeroed.set_initializer(Constant::i32(0, &context));
+            let value = module_builder.add_global(const_cstr!("value").as_cstr(), Type::i32(&context));
+            value.set_initializer(Constant::i32(7, &context));
+
+            let external_ty = Type::typed_function::<(), I32>(&context);
+            let external = module_builder.add_function(const_cstr!("external").as_cstr(), external_ty);
+
+            let read = module_builder.add_function(const_cstr!("read").as_cstr(), external_ty);
+            id::with(|function_id| {
+                let function_builder = read.builder(function_id);
+                let (_, entry) = function_builder.append_basic_block(const_cstr!("entry").as_cstr(), &context);
+                let builder = builder.position_at_end(entry);
+                builder.call(external_ty, external.label().as_value(), (), const_cstr!("").as_cstr());
+                let loaded = builder.load(Type::i32(&context), upcast(value.as_value()), const_cstr!("loaded").as_cstr());
+                builder.ret(upcast(loaded));
+            });
+            read.verify();
+        }
+
+        let buffer = machine.emit_module_to_memory(&module, CodeGenFileType::Object).unwrap();
+        let object = ObjectFile::new(&buffer).unwrap();
+
+        let section = |name: &[u8]| object.sections().find(|section| section.name().to_bytes() == name).expect("missing section");
+        assert_eq!(section(b".data").contents(&buffer), &7i32.to_le_bytes());
+        // `.bss` takes up space when loaded but none in the file.
+        assert_eq!(section(b".bss").size(), 4);
+        assert!(section(b".bss").contents(&buffer).is_empty());
+
+        let symbol = |name: &[u8]| object.symbols().find(|symbol| symbol.name().to_bytes() == name).expect("missing symbol");
+        assert_eq!(symbol(b"zeroed").size(), 4);
+        assert_eq!(symbol(b"value").size(), 4);
+        assert!(symbol(b"read").size() > 0);
+
+        // ELF lists relocations on the `.rela.text` section that applies them to `.text`.
+        let rela_text = section(b".rela.text");
+        let relocations = rela_text.relocations();
+        let relocation = |name: &[u8]| relocations.iter().find(|relocation| match relocation.symbol() {
+            Some(symbol) => symbol.name().to_bytes() == name,
+            None => false
+        }).expect("missing relocation");
+        let text_size = section(b".text").size();
+        assert_eq!(relocation(b"external").kind(), R_X86_64_PLT32);
+        assert!(relocation(b"external").offset() < text_size);
+        // Position independent code reaches the preemptible global through the GOT.
+        assert_eq!(relocation(b"value").kind(), R_X86_64_GOTPCREL);
+        assert!(relocation(b"value").offset() < text_size);
+    });
+}
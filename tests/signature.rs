@@ -0,0 +1,42 @@
+extern crate llvm_safe;
+#[macro_use] extern crate const_cstr_fork;
+
+use llvm_safe::id;
+use llvm_safe::inheritance::upcast;
+use llvm_safe::llvm::{Context, Module, Builder, Type, IntegerType, Constant, I32, I64, Void};
+
+#[test]
+fn typed_calls() {
+    id::with2(|context_id, module_id| {
+        let context = Context::new(context_id);
+        let mut module = Module::new(module_id, const_cstr!("signatures").as_cstr(), &context);
+        let module_builder = module.builder();
+        let mut builder = Builder::new(&context);
+
+        let second_ty = Type::typed_function::<(I32, I64), I64>(&context);
+        let second = module_builder.add_function(const_cstr!("second").as_cstr(), second_ty);
+        assert_eq!(second.label().num_args(), 2);
+        id::with(|function_id| {
+            let function_builder = second.builder(function_id);
+            let (_, b) = function_builder.typed_params();
+            let (_, entry) = function_builder.append_basic_block(const_cstr!("entry").as_cstr(), &context);
+            builder.position_at_end(entry).ret(upcast(b));
+        });
+        second.verify();
+
+        let caller_ty = Type::typed_function::<(I32,), Void>(&context);
+        let caller = module_builder.add_function(const_cstr!("caller").as_cstr(), caller_ty);
+        id::with(|function_id| {
+            let function_builder = caller.builder(function_id);
+            let (a,) = function_builder.typed_params();
+            let (_, entry) = function_builder.append_basic_block(const_cstr!("entry").as_cstr(), &context);
+            let builder = builder.position_at_end(entry);
+
+            let b = Constant::integer(7, Type::int::<I64>(&context), false).as_value();
+            let result = builder.call(second_ty, second.label().as_value(), (a, b), const_cstr!("result").as_cstr()).as_value();
+            assert_eq!(upcast::<_,IntegerType>(Type::of_value(result)).width(), 64);
+            builder.ret_void();
+        });
+        caller.verify();
+    });
+}